@@ -0,0 +1,155 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread::{spawn, JoinHandle},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use mlflow_client::{
+    client::MlflowClient,
+    data::{Metric, Timestamp},
+    MlflowRun, MlflowRunWriter, MlflowRunWriterOptions,
+};
+
+#[allow(dead_code)]
+#[path = "../tests/stand_in/mod.rs"]
+mod stand_in;
+
+use stand_in::tracking::FakeTracking;
+
+const MAX_QUEUE_SIZE: usize = 10000;
+
+/// Starts a run whose queue is bounded, so that the measured time includes sending logs to the server.
+fn start_run(s: &FakeTracking) -> MlflowRunWriter {
+    let e = s.mlflow().experiment("0").unwrap().unwrap();
+    let options = MlflowRunWriterOptions {
+        max_queue_size: Some(MAX_QUEUE_SIZE),
+        ..Default::default()
+    };
+    e.start_run_with_writer_options("bench", Default::default(), options)
+        .unwrap()
+}
+
+/// Baseline that sends logs in the way the writer did before it had a persistent worker:
+/// a thread is spawned when logs are written while no thread is sending,
+/// and it exits once the queue is empty. Logging waits for the thread while the queue is full.
+struct SpawnPerBurst {
+    run: MlflowRun,
+    queue: Arc<Mutex<(Vec<Metric>, bool)>>,
+    task: Option<JoinHandle<()>>,
+}
+impl SpawnPerBurst {
+    fn new(run: MlflowRun) -> Self {
+        Self {
+            run,
+            queue: Arc::new(Mutex::new((Vec::new(), false))),
+            task: None,
+        }
+    }
+    fn log_metric(&mut self, key: &str, value: f64, step: Option<i64>) {
+        if self.queue.lock().unwrap().0.len() >= MAX_QUEUE_SIZE {
+            self.flush();
+        }
+        let mut q = self.queue.lock().unwrap();
+        q.0.push(Metric {
+            key: key.to_string(),
+            value,
+            timestamp: Timestamp::now(),
+            step,
+            model_id: None,
+            dataset_name: None,
+            dataset_digest: None,
+        });
+        if q.1 {
+            return;
+        }
+        q.1 = true;
+        drop(q);
+        // The previous thread has emptied the queue and is exiting.
+        self.flush();
+        let run = self.run.clone();
+        let queue = self.queue.clone();
+        self.task = Some(spawn(move || loop {
+            let metrics = {
+                let mut q = queue.lock().unwrap();
+                if q.0.is_empty() {
+                    q.1 = false;
+                    return;
+                }
+                let n = q.0.len().min(MlflowClient::LOG_BATCH_MAX_METRICS);
+                q.0.drain(..n).collect::<Vec<_>>()
+            };
+            run.log_batch(&metrics, &[], &[]).unwrap();
+        }));
+    }
+    fn flush(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.join().unwrap();
+        }
+    }
+}
+
+fn log_metric(c: &mut Criterion) {
+    let s = FakeTracking::start();
+    let mut w = start_run(&s);
+    let mut step = 0;
+    c.bench_function("log_metric", |b| {
+        b.iter(|| {
+            w.log_metric("loss", 0.5, Some(step)).unwrap();
+            step += 1;
+        })
+    });
+    w.finish().unwrap();
+}
+
+fn log_metric_and_flush(c: &mut Criterion) {
+    let s = FakeTracking::start();
+    let mut w = start_run(&s);
+    let mut step = 0;
+    c.bench_function("log_metric_and_flush", |b| {
+        b.iter(|| {
+            w.log_metric("loss", 0.5, Some(step)).unwrap();
+            w.flush().unwrap();
+            step += 1;
+        })
+    });
+    w.finish().unwrap();
+}
+
+fn baseline_log_metric(c: &mut Criterion) {
+    let s = FakeTracking::start();
+    let w = start_run(&s);
+    let mut b = SpawnPerBurst::new(w.run().clone());
+    let mut step = 0;
+    c.bench_function("baseline_spawn_per_burst/log_metric", |bencher| {
+        bencher.iter(|| {
+            b.log_metric("loss", 0.5, Some(step));
+            step += 1;
+        })
+    });
+    b.flush();
+    w.finish().unwrap();
+}
+
+fn baseline_log_metric_and_flush(c: &mut Criterion) {
+    let s = FakeTracking::start();
+    let w = start_run(&s);
+    let mut b = SpawnPerBurst::new(w.run().clone());
+    let mut step = 0;
+    c.bench_function("baseline_spawn_per_burst/log_metric_and_flush", |bencher| {
+        bencher.iter(|| {
+            b.log_metric("loss", 0.5, Some(step));
+            b.flush();
+            step += 1;
+        })
+    });
+    w.finish().unwrap();
+}
+
+criterion_group!(
+    benches,
+    log_metric,
+    log_metric_and_flush,
+    baseline_log_metric,
+    baseline_log_metric_and_flush
+);
+criterion_main!(benches);
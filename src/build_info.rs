@@ -0,0 +1,211 @@
+//! Build provenance of the calling crate, recorded with [`MlflowRunWriter::log_build_info`](crate::MlflowRunWriter::log_build_info).
+//!
+//! The crate name and version are available without setup.
+//! To record the rustc version, target, profile, features and `Cargo.lock`,
+//! add `mlflow-client` to `[build-dependencies]` and call [`emit`] from the build script.
+//!
+//! # Examples
+//!
+//! In `main` of `build.rs`:
+//!
+//! ```no_run
+//! mlflow_client::build_info::emit();
+//! ```
+//!
+//! `main.rs`:
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+//! let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+//! let mut run = experiment.start_run("run_name")?;
+//! run.log_build_info(&mlflow_client::build_info!())?;
+//! run.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::data::RunTag;
+
+/// Tag that holds the name of the crate.
+pub const CRATE_NAME_TAG: &str = "rust.crate_name";
+
+/// Tag that holds the version of the crate.
+pub const CRATE_VERSION_TAG: &str = "rust.crate_version";
+
+/// Tag that holds the output of `rustc --version`.
+pub const RUSTC_VERSION_TAG: &str = "rust.rustc_version";
+
+/// Tag that holds the target triple.
+pub const TARGET_TAG: &str = "rust.target";
+
+/// Tag that holds the cargo profile.
+pub const PROFILE_TAG: &str = "rust.profile";
+
+/// Tag that holds the enabled cargo features, separated by commas.
+pub const FEATURES_TAG: &str = "rust.features";
+
+/// Tag that holds the SHA-256 hash of `Cargo.lock`.
+pub const CARGO_LOCK_SHA256_TAG: &str = "rust.cargo_lock_sha256";
+
+/// Tag that holds whether the git working tree has uncommitted changes.
+pub const GIT_DIRTY_TAG: &str = "rust.git_dirty";
+
+/// Artifact path of the uploaded `Cargo.lock`.
+pub const CARGO_LOCK_ARTIFACT: &str = "build/Cargo.lock";
+
+/// Artifact path of the uncommitted changes of the git working tree.
+pub const GIT_DIFF_ARTIFACT: &str = "build/git_diff.patch";
+
+/// Build provenance of a crate.
+///
+/// Use the [`build_info!`](crate::build_info!) macro to obtain the information of the calling crate.
+#[derive(Debug, Clone, Default)]
+pub struct BuildInfo {
+    pub crate_name: String,
+    pub crate_version: String,
+    /// Directory containing `Cargo.toml`, used to find uncommitted changes.
+    pub manifest_dir: PathBuf,
+    pub rustc_version: Option<String>,
+    pub target: Option<String>,
+    pub profile: Option<String>,
+    pub features: Option<Vec<String>>,
+    /// Path of `Cargo.lock`, read when the build information is logged.
+    pub cargo_lock: Option<PathBuf>,
+}
+
+/// Returns the [`BuildInfo`](crate::build_info::BuildInfo) of the calling crate.
+///
+/// Information other than the crate name and version is available only if
+/// [`build_info::emit`](crate::build_info::emit) is called from the build script.
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::build_info::BuildInfo {
+            crate_name: env!("CARGO_PKG_NAME").to_string(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            manifest_dir: env!("CARGO_MANIFEST_DIR").into(),
+            rustc_version: option_env!("MLFLOW_BUILD_RUSTC_VERSION").map(|s| s.to_string()),
+            target: option_env!("MLFLOW_BUILD_TARGET").map(|s| s.to_string()),
+            profile: option_env!("MLFLOW_BUILD_PROFILE").map(|s| s.to_string()),
+            features: option_env!("MLFLOW_BUILD_FEATURES").map(|s| {
+                s.split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect()
+            }),
+            cargo_lock: option_env!("MLFLOW_BUILD_CARGO_LOCK").map(|s| s.into()),
+        }
+    };
+}
+
+/// Passes the build information to [`build_info!`](crate::build_info!). Call this from the build script.
+///
+/// # Panics
+///
+/// Panics if it is not called from a build script.
+pub fn emit() {
+    let var = |name| {
+        env::var(name).unwrap_or_else(|_| {
+            panic!("`{name}` is not set. `emit` must be called from a build script.")
+        })
+    };
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Ok(output) = Command::new(rustc).arg("--version").output() {
+        let version = String::from_utf8_lossy(&output.stdout);
+        println!(
+            "cargo:rustc-env=MLFLOW_BUILD_RUSTC_VERSION={}",
+            version.trim()
+        );
+    }
+    println!("cargo:rustc-env=MLFLOW_BUILD_TARGET={}", var("TARGET"));
+    println!("cargo:rustc-env=MLFLOW_BUILD_PROFILE={}", var("PROFILE"));
+    let mut features = env::vars()
+        .filter_map(|(name, _)| {
+            let feature = name.strip_prefix("CARGO_FEATURE_")?;
+            Some(feature.to_lowercase().replace('_', "-"))
+        })
+        .collect::<Vec<_>>();
+    features.sort();
+    println!(
+        "cargo:rustc-env=MLFLOW_BUILD_FEATURES={}",
+        features.join(",")
+    );
+    let manifest_dir = PathBuf::from(var("CARGO_MANIFEST_DIR"));
+    if let Some(lock) = find_cargo_lock(&manifest_dir) {
+        println!("cargo:rustc-env=MLFLOW_BUILD_CARGO_LOCK={}", lock.display());
+        println!("cargo:rerun-if-changed={}", lock.display());
+    }
+}
+
+impl BuildInfo {
+    /// Returns the tags and artifacts to record.
+    pub(crate) fn collect(&self) -> (Vec<RunTag>, Vec<(&'static str, Vec<u8>)>) {
+        let mut tags = vec![
+            tag(CRATE_NAME_TAG, &self.crate_name),
+            tag(CRATE_VERSION_TAG, &self.crate_version),
+        ];
+        let mut artifacts = Vec::new();
+        for (key, value) in [
+            (RUSTC_VERSION_TAG, &self.rustc_version),
+            (TARGET_TAG, &self.target),
+            (PROFILE_TAG, &self.profile),
+        ] {
+            if let Some(value) = value {
+                tags.push(tag(key, value));
+            }
+        }
+        if let Some(features) = &self.features {
+            tags.push(tag(FEATURES_TAG, &features.join(",")));
+        }
+        if let Some(data) = self.cargo_lock.as_ref().and_then(|p| fs::read(p).ok()) {
+            let hash = Sha256::digest(&data)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>();
+            tags.push(tag(CARGO_LOCK_SHA256_TAG, &hash));
+            artifacts.push((CARGO_LOCK_ARTIFACT, data));
+        }
+        if let Some(diff) = git_diff(&self.manifest_dir) {
+            tags.push(tag(GIT_DIRTY_TAG, &(!diff.is_empty()).to_string()));
+            if !diff.is_empty() {
+                artifacts.push((GIT_DIFF_ARTIFACT, diff));
+            }
+        }
+        (tags, artifacts)
+    }
+}
+
+fn tag(key: &str, value: &str) -> RunTag {
+    RunTag {
+        key: key.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Returns the uncommitted changes of the git working tree containing `dir`,
+/// or `None` if `dir` is not in a git working tree.
+fn git_diff(dir: &Path) -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["diff", "HEAD"])
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+fn find_cargo_lock(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,213 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{data::Timestamp, utils::none_if_not_exist, Error, MlflowRun, Result};
+
+/// Artifact path of the index of the checkpoints of a run.
+///
+/// The files of each checkpoint are stored under `checkpoints/step-{step}/`.
+pub const CHECKPOINT_INDEX_ARTIFACT: &str = "checkpoints/index.json";
+
+/// A checkpoint saved by [`MlflowRunWriter::save_checkpoint`](crate::MlflowRunWriter::save_checkpoint).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub step: i64,
+    pub timestamp: Timestamp,
+    /// Artifact path of the directory containing the files of the checkpoint.
+    pub path: String,
+    /// Paths of the files relative to [`path`](Self::path), separated by `/`.
+    pub files: Vec<String>,
+    /// Latest values of the metrics of the run when the checkpoint was saved.
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// Whether a smaller or larger value of a metric is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricGoal {
+    Minimize,
+    Maximize,
+}
+
+/// Checkpoints kept by [`MlflowRunWriter::save_checkpoint`](crate::MlflowRunWriter::save_checkpoint).
+///
+/// A checkpoint is kept if it is selected by either `keep_last` or `keep_best`.
+/// If both are `None`, all checkpoints are kept.
+///
+/// Use with [`MlflowRunWriterOptions::checkpoint_policy`](crate::MlflowRunWriterOptions::checkpoint_policy).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CheckpointPolicy {
+    /// Number of checkpoints with the largest steps to keep.
+    pub keep_last: Option<usize>,
+    pub keep_best: Option<KeepBest>,
+}
+
+/// Keeps the checkpoints with the best values of a metric.
+///
+/// Checkpoints saved before the metric was logged are not selected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeepBest {
+    pub metric: String,
+    pub goal: MetricGoal,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CheckpointIndex {
+    checkpoints: Vec<Checkpoint>,
+}
+
+/// Returns the checkpoints of the run in ascending order of step.
+pub(crate) fn checkpoints(run: &MlflowRun) -> Result<Vec<Checkpoint>> {
+    let index = none_if_not_exist(run.download_artifact(CHECKPOINT_INDEX_ARTIFACT), |data| {
+        Ok(serde_json::from_slice::<CheckpointIndex>(&data)?)
+    })?;
+    Ok(index.unwrap_or_default().checkpoints)
+}
+
+/// Uploads the files in `dir` as the checkpoint of `step`, and removes the checkpoints not kept by `policy`.
+///
+/// A checkpoint of the same step is replaced.
+pub(crate) fn save(
+    run: &MlflowRun,
+    step: i64,
+    dir: &Path,
+    metrics: BTreeMap<String, f64>,
+    policy: &CheckpointPolicy,
+) -> Result<Checkpoint> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut Vec::new(), &mut files)?;
+    let checkpoint = Checkpoint {
+        step,
+        timestamp: Timestamp::now(),
+        path: format!("checkpoints/step-{step}"),
+        files: files.iter().map(|(name, _)| name.clone()).collect(),
+        metrics,
+    };
+    for (name, path) in &files {
+        run.log_artifact(&format!("{}/{name}", checkpoint.path), fs::read(path)?)?;
+    }
+    let mut removed = Vec::new();
+    let mut all = Vec::new();
+    for c in checkpoints(run)? {
+        if c.step == step {
+            removed.extend(
+                c.files
+                    .iter()
+                    .filter(|f| !checkpoint.files.contains(f))
+                    .map(|f| format!("{}/{f}", c.path)),
+            );
+        } else {
+            all.push(c);
+        }
+    }
+    all.push(checkpoint.clone());
+    all.sort_by_key(|c| c.step);
+    let kept = kept_steps(&all, policy);
+    let (kept, pruned): (Vec<_>, Vec<_>) = all.into_iter().partition(|c| kept.contains(&c.step));
+    for c in &pruned {
+        removed.extend(c.files.iter().map(|f| format!("{}/{f}", c.path)));
+    }
+    let index = CheckpointIndex { checkpoints: kept };
+    run.log_artifact(CHECKPOINT_INDEX_ARTIFACT, serde_json::to_vec(&index)?)?;
+    for path in removed {
+        run.delete_artifact(&path)?;
+    }
+    Ok(checkpoint)
+}
+
+/// Returns the steps of the checkpoints kept by `policy`. `checkpoints` must be sorted by step.
+fn kept_steps(checkpoints: &[Checkpoint], policy: &CheckpointPolicy) -> HashSet<i64> {
+    if policy.keep_last.is_none() && policy.keep_best.is_none() {
+        return checkpoints.iter().map(|c| c.step).collect();
+    }
+    let mut kept = HashSet::new();
+    if let Some(n) = policy.keep_last {
+        kept.extend(checkpoints.iter().rev().take(n).map(|c| c.step));
+    }
+    if let Some(best) = &policy.keep_best {
+        let mut ranked = checkpoints
+            .iter()
+            .filter_map(|c| Some((*c.metrics.get(&best.metric)?, c.step)))
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| match best.goal {
+            MetricGoal::Minimize => a.0.total_cmp(&b.0),
+            MetricGoal::Maximize => b.0.total_cmp(&a.0),
+        });
+        kept.extend(ranked.iter().take(best.count).map(|(_, step)| *step));
+    }
+    kept
+}
+
+/// Returns the checkpoint with the best value of `metric`.
+pub(crate) fn best(
+    checkpoints: Vec<Checkpoint>,
+    metric: &str,
+    goal: MetricGoal,
+) -> Option<Checkpoint> {
+    checkpoints
+        .into_iter()
+        .filter_map(|c| Some((*c.metrics.get(metric)?, c)))
+        .reduce(|a, b| {
+            let b_is_better = match goal {
+                MetricGoal::Minimize => b.0 < a.0,
+                MetricGoal::Maximize => b.0 > a.0,
+            };
+            if b_is_better {
+                b
+            } else {
+                a
+            }
+        })
+        .map(|(_, c)| c)
+}
+
+/// Downloads the files of `checkpoint` into `dir`.
+///
+/// Returns an error without downloading any file if a file name would be written outside `dir`.
+pub(crate) fn download(run: &MlflowRun, checkpoint: &Checkpoint, dir: &Path) -> Result<()> {
+    for name in &checkpoint.files {
+        let path = Path::new(name);
+        if path.as_os_str().is_empty()
+            || !path.components().all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(Error::from_message(format!(
+                "Invalid file name in checkpoint: {name:?}"
+            )));
+        }
+    }
+    for name in &checkpoint.files {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            path,
+            run.download_artifact(&format!("{}/{name}", checkpoint.path))?,
+        )?;
+    }
+    Ok(())
+}
+
+fn collect_files(
+    dir: &Path,
+    prefix: &mut Vec<String>,
+    files: &mut Vec<(String, PathBuf)>,
+) -> Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        prefix.push(entry.file_name().to_string_lossy().into_owned());
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), prefix, files)?;
+        } else {
+            files.push((prefix.join("/"), entry.path()));
+        }
+        prefix.pop();
+    }
+    Ok(())
+}
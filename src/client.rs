@@ -2,34 +2,45 @@ use std::sync::Arc;
 
 use reqwest::{
     blocking::{Client, Response},
-    Url,
+    Method, StatusCode, Url,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{json, Value};
 
 use crate::{
     data::{
-        CreateExperimentOptions, CreateRunOptions, DatasetInput, Metric, Param, RunTag,
-        SearchExperimentsOptions, SearchRunsOptions, Timestamp, UpdateRunOptions,
+        CreateExperimentOptions, CreateLoggedModelOptions, CreateRunOptions, DatasetInput,
+        DeleteTracesOptions, EndTraceOptions, LoggedModelParameter, LoggedModelStatus,
+        LoggedModelTag, Metric, Param, RunTag, SearchExperimentsOptions, SearchLoggedModelsOptions,
+        SearchRunsOptions, SearchTracesOptions, StartTraceOptions, Timestamp, TraceData, TraceInfo,
+        TraceStatus, UpdateRunOptions,
     },
     Error, Result,
 };
 
 pub mod response;
+pub mod serving;
 
 use response::*;
 
 #[derive(Debug, Clone)]
 pub struct MlflowClient {
     uri: Arc<Url>,
+    http: Client,
 }
 
 impl MlflowClient {
     pub fn new(uri: &str) -> Result<MlflowClient> {
         Ok(MlflowClient {
             uri: Arc::new(Url::parse(uri)?),
+            http: Client::new(),
         })
     }
+
+    /// Returns a client for the scoring server at `uri` that shares the connection pool of this client.
+    pub fn model_serving_client(&self, uri: &str) -> Result<serving::ModelServingClient> {
+        serving::ModelServingClient::with_http(uri, self.http.clone())
+    }
     /// <https://mlflow.org/docs/latest/rest-api.html#create-experiment>
     pub fn create_experiment(
         &self,
@@ -303,16 +314,271 @@ impl MlflowClient {
         self.post("runs/update", body)
     }
 
+    /// <https://mlflow.org/docs/latest/rest-api.html#create-logged-model>
+    pub fn create_logged_model(
+        &self,
+        experiment_id: &str,
+        name: &str,
+        options: CreateLoggedModelOptions,
+    ) -> Result<GetLoggedModelResponse> {
+        let body = build_body(
+            json!({ "experiment_id": experiment_id, "name": name }),
+            options,
+        )?;
+        self.post("logged-models", body)
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#get-logged-model>
+    pub fn get_logged_model(&self, model_id: &str) -> Result<GetLoggedModelResponse> {
+        let url = self.url_with_segments("logged-models", &[model_id])?;
+        to_result(self.http.get(url).send()?)
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#finalize-logged-model>
+    pub fn finalize_logged_model(
+        &self,
+        model_id: &str,
+        status: LoggedModelStatus,
+    ) -> Result<GetLoggedModelResponse> {
+        let url = self.url_with_segments("logged-models", &[model_id])?;
+        self.send(
+            Method::PATCH,
+            url,
+            json!({ "model_id": model_id, "status": status }),
+        )
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#delete-logged-model>
+    pub fn delete_logged_model(&self, model_id: &str) -> Result<UnitResponse> {
+        let url = self.url_with_segments("logged-models", &[model_id])?;
+        self.send(Method::DELETE, url, json!({ "model_id": model_id }))
+    }
+
+    pub const SEARCH_LOGGED_MODELS_MAX_RESULTS_SUPPORTED: i32 = 100;
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#search-logged-models>
+    pub fn search_logged_models(
+        &self,
+        experiment_ids: &[&str],
+        options: SearchLoggedModelsOptions,
+        max_results: i32,
+        page_token: Option<&str>,
+    ) -> Result<SearchLoggedModelsResponse> {
+        let body = build_body(
+            json!({ "experiment_ids": experiment_ids, "max_results": max_results, "page_token": page_token }),
+            options,
+        )?;
+        self.post("logged-models/search", body)
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#set-logged-model-tags>
+    pub fn set_logged_model_tags(
+        &self,
+        model_id: &str,
+        tags: &[LoggedModelTag],
+    ) -> Result<UnitResponse> {
+        let url = self.url_with_segments("logged-models", &[model_id, "tags"])?;
+        self.send(
+            Method::PATCH,
+            url,
+            json!({ "model_id": model_id, "tags": tags }),
+        )
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#delete-logged-model-tag>
+    pub fn delete_logged_model_tag(&self, model_id: &str, tag_key: &str) -> Result<UnitResponse> {
+        let url = self.url_with_segments("logged-models", &[model_id, "tags", tag_key])?;
+        self.send(
+            Method::DELETE,
+            url,
+            json!({ "model_id": model_id, "tag_key": tag_key }),
+        )
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#log-logged-model-params>
+    pub fn log_logged_model_params(
+        &self,
+        model_id: &str,
+        params: &[LoggedModelParameter],
+    ) -> Result<UnitResponse> {
+        let url = self.url_with_segments("logged-models", &[model_id, "params"])?;
+        self.send(
+            Method::POST,
+            url,
+            json!({ "model_id": model_id, "params": params }),
+        )
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#start-trace>
+    pub fn start_trace(
+        &self,
+        experiment_id: &str,
+        timestamp_ms: Timestamp,
+        options: StartTraceOptions,
+    ) -> Result<GetTraceInfoResponse> {
+        let body = build_body(
+            json!({ "experiment_id": experiment_id, "timestamp_ms": timestamp_ms }),
+            options,
+        )?;
+        self.post("traces", body)
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#end-trace>
+    pub fn end_trace(
+        &self,
+        request_id: &str,
+        timestamp_ms: Timestamp,
+        status: TraceStatus,
+        options: EndTraceOptions,
+    ) -> Result<GetTraceInfoResponse> {
+        let body = build_body(
+            json!({ "request_id": request_id, "timestamp_ms": timestamp_ms, "status": status }),
+            options,
+        )?;
+        let url = self.url_with_segments("traces", &[request_id])?;
+        self.send(Method::PATCH, url, body)
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#get-trace-info>
+    pub fn get_trace_info(&self, request_id: &str) -> Result<GetTraceInfoResponse> {
+        let url = self.url_with_segments("traces", &[request_id, "info"])?;
+        to_result(self.http.get(url).send()?)
+    }
+
+    pub const SEARCH_TRACES_MAX_RESULTS_SUPPORTED: i32 = 500;
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#search-traces>
+    pub fn search_traces(
+        &self,
+        experiment_ids: &[&str],
+        options: SearchTracesOptions,
+        max_results: i32,
+        page_token: Option<&str>,
+    ) -> Result<SearchTracesResponse> {
+        let max_results = max_results.to_string();
+        let mut query = vec![
+            ("filter", options.filter),
+            ("max_results", &max_results),
+            ("page_token", page_token.unwrap_or("")),
+        ];
+        query.extend(experiment_ids.iter().map(|id| ("experiment_ids", *id)));
+        query.extend(options.order_by.iter().map(|o| ("order_by", *o)));
+        self.get("traces", &query)
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#delete-traces>
+    pub fn delete_traces(
+        &self,
+        experiment_id: &str,
+        options: DeleteTracesOptions,
+    ) -> Result<DeleteTracesResponse> {
+        let body = build_body(json!({ "experiment_id": experiment_id }), options)?;
+        self.post("traces/delete-traces", body)
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#set-trace-tag>
+    pub fn set_trace_tag(&self, request_id: &str, key: &str, value: &str) -> Result<UnitResponse> {
+        let url = self.url_with_segments("traces", &[request_id, "tags"])?;
+        self.send(Method::PATCH, url, json!({ "key": key, "value": value }))
+    }
+
+    /// <https://mlflow.org/docs/latest/rest-api.html#delete-trace-tag>
+    pub fn delete_trace_tag(&self, request_id: &str, key: &str) -> Result<UnitResponse> {
+        let url = self.url_with_segments("traces", &[request_id, "tags"])?;
+        self.send(Method::DELETE, url, json!({ "key": key }))
+    }
+
+    /// Uploads the spans of a trace to the artifact location recorded in `trace_info`.
+    pub fn upload_trace_data(&self, trace_info: &TraceInfo, data: &TraceData) -> Result<()> {
+        let artifact_uri = trace_artifact_location(trace_info)?;
+        self.upload_artifact(artifact_uri, "traces.json", serde_json::to_vec(data)?)
+    }
+
+    /// Downloads the spans of a trace from the artifact location recorded in `trace_info`.
+    pub fn get_trace_data(&self, trace_info: &TraceInfo) -> Result<TraceData> {
+        let artifact_uri = trace_artifact_location(trace_info)?;
+        Ok(serde_json::from_slice(
+            &self.download_artifact(artifact_uri, "traces.json")?,
+        )?)
+    }
+
+    /// Uploads a file to `path` under `artifact_uri`.
+    ///
+    /// `artifact_uri` must be a `mlflow-artifacts:` URI,
+    /// which is used when the tracking server proxies artifact access (the default since MLflow 2.0).
+    pub fn upload_artifact(&self, artifact_uri: &str, path: &str, data: Vec<u8>) -> Result<()> {
+        let url = self.artifact_url(artifact_uri, path)?;
+        let _: UnitResponse = to_result(self.http.put(url).body(data).send()?)?;
+        Ok(())
+    }
+
+    /// Downloads a file at `path` under `artifact_uri`.
+    ///
+    /// See [`upload_artifact`](Self::upload_artifact) for the supported URIs.
+    pub fn download_artifact(&self, artifact_uri: &str, path: &str) -> Result<Vec<u8>> {
+        let url = self.artifact_url(artifact_uri, path)?;
+        let r = self.http.get(url).send()?;
+        if r.status().is_success() {
+            Ok(r.bytes()?.to_vec())
+        } else if r.status() == StatusCode::NOT_FOUND {
+            Err(Error::ApiError {
+                error_code: "RESOURCE_DOES_NOT_EXIST".to_string(),
+                message: format!("Artifact not found: {path}"),
+            })
+        } else {
+            to_result(r)
+        }
+    }
+
+    /// Deletes a file at `path` under `artifact_uri`.
+    ///
+    /// See [`upload_artifact`](Self::upload_artifact) for the supported URIs.
+    pub fn delete_artifact(&self, artifact_uri: &str, path: &str) -> Result<()> {
+        let url = self.artifact_url(artifact_uri, path)?;
+        let _: UnitResponse = to_result(self.http.delete(url).send()?)?;
+        Ok(())
+    }
+
+    fn artifact_url(&self, artifact_uri: &str, path: &str) -> Result<Url> {
+        let uri = Url::parse(artifact_uri)?;
+        if uri.scheme() != "mlflow-artifacts" {
+            return Err(Error::from_message(format!(
+                "Unsupported artifact URI: {artifact_uri}"
+            )));
+        }
+        let mut url = self.uri.join("/api/2.0/mlflow-artifacts/artifacts")?;
+        url.path_segments_mut()
+            .map_err(|_| Error::from_message("URL cannot be a base"))?
+            .extend(uri.path().split('/').filter(|s| !s.is_empty()))
+            .extend(path.split('/').filter(|s| !s.is_empty()));
+        Ok(url)
+    }
+
     fn post<T: DeserializeOwned>(&self, path: &str, body: impl Serialize) -> Result<T> {
-        to_result(Client::new().post(self.url(path)?).json(&body).send()?)
+        self.send(Method::POST, self.url(path)?, body)
     }
     fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
-        to_result(Client::new().get(self.url(path)?).query(query).send()?)
+        to_result(self.http.get(self.url(path)?).query(query).send()?)
+    }
+    fn send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: Url,
+        body: impl Serialize,
+    ) -> Result<T> {
+        to_result(self.http.request(method, url).json(&body).send()?)
     }
 
     fn url(&self, path: &str) -> Result<Url> {
         Ok(self.uri.join("/api/2.0/mlflow/")?.join(path)?)
     }
+    fn url_with_segments(&self, path: &str, segments: &[&str]) -> Result<Url> {
+        let mut url = self.url(path)?;
+        url.path_segments_mut()
+            .map_err(|_| Error::from_message("URL cannot be a base"))?
+            .extend(segments);
+        Ok(url)
+    }
 }
 impl Default for MlflowClient {
     fn default() -> Self {
@@ -331,6 +597,16 @@ fn to_result<T: DeserializeOwned>(r: Response) -> Result<T> {
         })
     }
 }
+fn trace_artifact_location(trace_info: &TraceInfo) -> Result<&str> {
+    trace_info
+        .tag(TraceInfo::ARTIFACT_LOCATION_TAG)
+        .ok_or_else(|| {
+            Error::from_message(format!(
+                "Trace {} has no artifact location",
+                trace_info.request_id
+            ))
+        })
+}
 fn build_body(json: Value, options: impl Serialize) -> Result<Value> {
     let Value::Object(mut l) = json else {
         panic!("l: expected object");
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::data::{Experiment, FileInfo, Metric, Run, RunInfo};
+use crate::data::{Experiment, FileInfo, LoggedModel, Metric, Run, RunInfo, TraceInfo};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreateExperimentResponse {
@@ -49,6 +49,36 @@ pub struct UpdateRunResponse {
     pub run_info: RunInfo,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetLoggedModelResponse {
+    pub model: LoggedModel,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchLoggedModelsResponse {
+    #[serde(default)]
+    pub models: Vec<LoggedModel>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetTraceInfoResponse {
+    pub trace_info: TraceInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchTracesResponse {
+    #[serde(default)]
+    pub traces: Vec<TraceInfo>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeleteTracesResponse {
+    #[serde(default)]
+    pub traces_deleted: i32,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ErrorResponse {
     pub error_code: String,
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use reqwest::{blocking::Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::to_result;
+use crate::{Error, Result};
+
+/// Client for the [scoring server] started by `mlflow models serve`.
+///
+/// [scoring server]: https://mlflow.org/docs/latest/deployment/deploy-model-locally.html
+#[derive(Debug, Clone)]
+pub struct ModelServingClient {
+    uri: Arc<Url>,
+    http: Client,
+}
+
+impl ModelServingClient {
+    pub fn new(uri: &str) -> Result<ModelServingClient> {
+        Self::with_http(uri, Client::new())
+    }
+    pub(super) fn with_http(uri: &str, http: Client) -> Result<ModelServingClient> {
+        Ok(ModelServingClient {
+            uri: Arc::new(Url::parse(uri)?),
+            http,
+        })
+    }
+
+    /// <https://mlflow.org/docs/latest/deployment/deploy-model-locally.html#inference-server-specification>
+    pub fn invocations(
+        &self,
+        input: &ServingInput,
+        params: Option<&Map<String, Value>>,
+    ) -> Result<InvocationsResponse> {
+        let mut body = serde_json::to_value(input)?;
+        if let Some(params) = params {
+            body["params"] = Value::Object(params.clone());
+        }
+        let value: Value = to_result(
+            self.http
+                .post(self.url("invocations")?)
+                .json(&body)
+                .send()?,
+        )?;
+        // Servers older than MLflow 2.0 return predictions without the `predictions` wrapper.
+        let predictions = match value {
+            Value::Object(mut m) if m.contains_key("predictions") => {
+                m.remove("predictions").unwrap()
+            }
+            value => value,
+        };
+        Ok(InvocationsResponse { predictions })
+    }
+
+    /// Sends `input` to `/invocations` and deserializes `predictions` from the response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use mlflow_client::client::serving::{ModelServingClient, ServingInput};
+    ///
+    /// let client = ModelServingClient::new("http://localhost:5001")?;
+    /// let input = ServingInput::Inputs(serde_json::json!([[1.0, 2.0], [3.0, 4.0]]));
+    /// let predictions: Vec<f64> = client.predict(&input, None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<T: DeserializeOwned>(
+        &self,
+        input: &ServingInput,
+        params: Option<&Map<String, Value>>,
+    ) -> Result<T> {
+        Ok(serde_json::from_value(
+            self.invocations(input, params)?.predictions,
+        )?)
+    }
+
+    /// Returns `Ok(())` if the server is ready to accept requests.
+    pub fn ping(&self) -> Result<()> {
+        self.get_text("ping")?;
+        Ok(())
+    }
+
+    /// Returns `Ok(())` if the server is healthy.
+    pub fn health(&self) -> Result<()> {
+        self.get_text("health")?;
+        Ok(())
+    }
+
+    /// Returns the MLflow version of the server.
+    pub fn version(&self) -> Result<String> {
+        Ok(self.get_text("version")?.trim().to_string())
+    }
+
+    fn get_text(&self, path: &str) -> Result<String> {
+        let r = self.http.get(self.url(path)?).send()?;
+        if r.status().is_success() {
+            Ok(r.text()?)
+        } else {
+            Err(Error::ApiError {
+                error_code: r.status().to_string(),
+                message: r.text()?,
+            })
+        }
+    }
+
+    fn url(&self, path: &str) -> Result<Url> {
+        Ok(self.uri.join(path)?)
+    }
+}
+
+/// Input of [`ModelServingClient::invocations`].
+///
+/// <https://mlflow.org/docs/latest/deployment/deploy-model-locally.html#json-input>
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServingInput {
+    /// Pandas DataFrame in the `split` orientation.
+    DataframeSplit(DataframeSplit),
+    /// Pandas DataFrame in the `records` orientation.
+    DataframeRecords(Vec<Map<String, Value>>),
+    /// Tensor input in the TF serving row format.
+    Instances(Value),
+    /// Tensor input in the TF serving columnar format.
+    Inputs(Value),
+}
+
+/// Pandas DataFrame in the `split` orientation.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct DataframeSplit {
+    pub columns: Vec<String>,
+    pub data: Vec<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<Vec<Value>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvocationsResponse {
+    pub predictions: Value,
+}
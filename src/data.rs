@@ -1,8 +1,9 @@
-use std::time::SystemTime;
+use std::{collections::BTreeMap, time::SystemTime};
 
 use derive_ex::Ex;
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use thiserror::Error;
 
 /// <https://mlflow.org/docs/latest/rest-api.html#fileinfo>
@@ -19,6 +20,26 @@ pub struct Run {
     pub info: RunInfo,
     pub data: RunData,
     pub inputs: RunInputs,
+    #[serde(default)]
+    pub outputs: RunOutputs,
+}
+impl Run {
+    /// Tag that holds the ID of the parent run of a nested run.
+    pub const PARENT_RUN_ID_TAG: &'static str = "mlflow.parentRunId";
+
+    /// Tag that holds the JSON list of artifacts shown in the evaluation view, such as tables.
+    pub const LOGGED_ARTIFACTS_TAG: &'static str = "mlflow.loggedArtifacts";
+
+    /// Tag that indicates that the run has images logged by `log_image`.
+    pub const LOGGED_IMAGES_TAG: &'static str = "mlflow.loggedImages";
+
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.data
+            .tags
+            .iter()
+            .find(|t| t.key == key)
+            .map(|t| t.value.as_str())
+    }
 }
 
 /// <https://mlflow.org/docs/latest/rest-api.html#runinfo>
@@ -32,6 +53,7 @@ pub struct RunInfo {
     pub end_time: Option<Timestamp>,
     pub artifact_uri: String,
     pub lifecycle_stage: String,
+    pub user_id: Option<String>,
 }
 
 /// <https://mlflow.org/docs/latest/rest-api.html#rundata>
@@ -54,6 +76,12 @@ pub struct Metric {
     pub value: f64,
     pub timestamp: Timestamp,
     pub step: Option<i64>,
+    /// ID of the [`LoggedModel`] associated with this metric.
+    pub model_id: Option<String>,
+    /// Name of the [`Dataset`] on which this metric was computed.
+    pub dataset_name: Option<String>,
+    /// Digest of the [`Dataset`] on which this metric was computed.
+    pub dataset_digest: Option<String>,
 }
 
 /// <https://mlflow.org/docs/latest/rest-api.html#param>
@@ -68,6 +96,28 @@ pub struct Param {
 pub struct RunInputs {
     #[serde(default)]
     pub dataset_inputs: Vec<DatasetInput>,
+    #[serde(default)]
+    pub model_inputs: Vec<ModelInput>,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#runoutputs>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct RunOutputs {
+    #[serde(default)]
+    pub model_outputs: Vec<ModelOutput>,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#modelinput>
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ModelInput {
+    pub model_id: String,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#modeloutput>
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ModelOutput {
+    pub model_id: String,
+    pub step: i64,
 }
 
 /// <https://mlflow.org/docs/latest/rest-api.html#datasetinput>
@@ -143,6 +193,199 @@ pub struct RunTag {
     pub value: String,
 }
 
+/// <https://mlflow.org/docs/latest/rest-api.html#loggedmodel>
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LoggedModel {
+    pub info: LoggedModelInfo,
+    #[serde(default)]
+    pub data: LoggedModelData,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#loggedmodelinfo>
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LoggedModelInfo {
+    pub model_id: String,
+    pub experiment_id: String,
+    pub name: String,
+    pub creation_timestamp_ms: Timestamp,
+    pub last_updated_timestamp_ms: Timestamp,
+    pub artifact_uri: String,
+    pub status: LoggedModelStatus,
+    pub creator_id: Option<i64>,
+    pub model_type: Option<String>,
+    pub source_run_id: Option<String>,
+    pub status_message: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<LoggedModelTag>,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#loggedmodeldata>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LoggedModelData {
+    #[serde(default)]
+    pub params: Vec<LoggedModelParameter>,
+    #[serde(default)]
+    pub metrics: Vec<Metric>,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#loggedmodelstatus>
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LoggedModelStatus {
+    #[serde(rename = "LOGGED_MODEL_STATUS_UNSPECIFIED")]
+    Unspecified,
+    #[serde(rename = "LOGGED_MODEL_PENDING")]
+    Pending,
+    #[serde(rename = "LOGGED_MODEL_READY")]
+    Ready,
+    #[serde(rename = "LOGGED_MODEL_UPLOAD_FAILED")]
+    UploadFailed,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#loggedmodeltag>
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LoggedModelTag {
+    pub key: String,
+    pub value: String,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#loggedmodelparameter>
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LoggedModelParameter {
+    pub key: String,
+    pub value: String,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#traceinfo>
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TraceInfo {
+    pub request_id: String,
+    pub experiment_id: String,
+    pub timestamp_ms: Timestamp,
+    pub execution_time_ms: Option<i64>,
+    pub status: TraceStatus,
+    #[serde(default)]
+    pub request_metadata: Vec<TraceRequestMetadata>,
+    #[serde(default)]
+    pub tags: Vec<TraceTag>,
+}
+impl TraceInfo {
+    /// Tag that holds the artifact location of the trace data.
+    pub const ARTIFACT_LOCATION_TAG: &'static str = "mlflow.artifactLocation";
+
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|t| t.key == key)
+            .map(|t| t.value.as_str())
+    }
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#tracestatus>
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum TraceStatus {
+    #[serde(rename = "TRACE_STATUS_UNSPECIFIED")]
+    Unspecified,
+    #[serde(rename = "OK")]
+    Ok,
+    #[serde(rename = "ERROR")]
+    Error,
+    #[serde(rename = "IN_PROGRESS")]
+    InProgress,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#tracerequestmetadata>
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TraceRequestMetadata {
+    pub key: String,
+    pub value: String,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#tracetag>
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TraceTag {
+    pub key: String,
+    pub value: String,
+}
+
+/// Spans of a trace, stored as the `traces.json` artifact of the trace.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TraceData {
+    #[serde(default)]
+    pub spans: Vec<Span>,
+}
+
+/// A span in [`TraceData`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Span {
+    pub name: String,
+    pub context: SpanContext,
+    pub parent_id: Option<String>,
+    /// Unix timestamp in nanoseconds.
+    pub start_time: i64,
+    /// Unix timestamp in nanoseconds.
+    pub end_time: Option<i64>,
+    pub status_code: SpanStatusCode,
+    #[serde(default)]
+    pub status_message: String,
+    /// Attribute values encoded as JSON strings.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+    #[serde(default)]
+    pub events: Vec<SpanEvent>,
+}
+impl Span {
+    pub const REQUEST_ID_ATTRIBUTE: &'static str = "mlflow.traceRequestId";
+    pub const SPAN_TYPE_ATTRIBUTE: &'static str = "mlflow.spanType";
+    pub const INPUTS_ATTRIBUTE: &'static str = "mlflow.spanInputs";
+    pub const OUTPUTS_ATTRIBUTE: &'static str = "mlflow.spanOutputs";
+
+    /// Returns the attribute value decoded from JSON.
+    pub fn attribute(&self, key: &str) -> Option<Value> {
+        serde_json::from_str(self.attributes.get(key)?).ok()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SpanContext {
+    pub span_id: String,
+    pub trace_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpanEvent {
+    pub name: String,
+    /// Unix timestamp in nanoseconds.
+    pub timestamp: i64,
+    #[serde(default)]
+    pub attributes: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SpanStatusCode {
+    #[default]
+    Unset,
+    Ok,
+    Error,
+}
+
+/// Value of the [`Span::SPAN_TYPE_ATTRIBUTE`] attribute.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SpanType {
+    Llm,
+    Chain,
+    Agent,
+    Tool,
+    ChatModel,
+    Retriever,
+    Parser,
+    Embedding,
+    Reranker,
+    #[default]
+    Unknown,
+}
+
 /// Unix timestamp in milliseconds.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(transparent)]
@@ -239,3 +482,61 @@ pub struct UpdateRunOptions<'a> {
     pub end_time: Option<Timestamp>,
     pub run_name: Option<&'a str>,
 }
+
+/// <https://mlflow.org/docs/latest/rest-api.html#mlflowcreateloggedmodel>
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct CreateLoggedModelOptions<'a> {
+    pub model_type: Option<&'a str>,
+    pub source_run_id: Option<&'a str>,
+    pub params: &'a [LoggedModelParameter],
+    pub tags: &'a [LoggedModelTag],
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#mlflowsearchloggedmodels>
+#[derive(Serialize, Debug, Clone, Copy, Ex)]
+#[derive_ex(Default)]
+pub struct SearchLoggedModelsOptions<'a> {
+    pub filter: &'a str,
+    pub order_by: &'a [SearchLoggedModelsOrderBy<'a>],
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#searchloggedmodelsorderby>
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct SearchLoggedModelsOrderBy<'a> {
+    pub field_name: &'a str,
+    pub ascending: bool,
+    pub dataset_name: Option<&'a str>,
+    pub dataset_digest: Option<&'a str>,
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#mlflowstarttrace>
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct StartTraceOptions<'a> {
+    pub request_metadata: &'a [TraceRequestMetadata],
+    pub tags: &'a [TraceTag],
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#mlflowendtrace>
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct EndTraceOptions<'a> {
+    pub request_metadata: &'a [TraceRequestMetadata],
+    pub tags: &'a [TraceTag],
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#mlflowsearchtraces>
+#[derive(Serialize, Debug, Clone, Copy, Ex)]
+#[derive_ex(Default)]
+pub struct SearchTracesOptions<'a> {
+    pub filter: &'a str,
+    pub order_by: &'a [&'a str],
+}
+
+/// <https://mlflow.org/docs/latest/rest-api.html#mlflowdeletetraces>
+///
+/// Either `max_timestamp_millis` or `request_ids` must be specified.
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct DeleteTracesOptions<'a> {
+    pub max_timestamp_millis: Option<Timestamp>,
+    pub max_traces: Option<i32>,
+    pub request_ids: &'a [&'a str],
+}
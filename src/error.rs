@@ -12,6 +12,8 @@ pub enum Error {
     JsonError(#[from] serde_json::Error),
     #[error("URL parse error: {0}")]
     UrlParseError(#[from] url::ParseError),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
     #[error("Task join failed")]
     TaskJoinError,
     #[error("Error: {0}")]
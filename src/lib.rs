@@ -2,18 +2,39 @@ use thiserror::Error;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+mod checkpoint;
 mod error;
 mod mlflow;
 mod mlflow_experiment;
+mod mlflow_logged_model;
 mod mlflow_run;
 mod mlflow_run_writer;
+mod mlflow_tracer;
+#[cfg(feature = "image")]
+mod run_image;
 mod utils;
 
+pub use checkpoint::{
+    Checkpoint, CheckpointPolicy, KeepBest, MetricGoal, CHECKPOINT_INDEX_ARTIFACT,
+};
 pub use error::Error;
 pub use mlflow::Mlflow;
 pub use mlflow_experiment::MlflowExperiment;
+pub use mlflow_logged_model::MlflowLoggedModel;
 pub use mlflow_run::MlflowRun;
-pub use mlflow_run_writer::MlflowRunWriter;
+pub use mlflow_run_writer::{
+    Aggregation, AggregationWindow, ErrorPolicy, MlflowRunLogger, MlflowRunProgress,
+    MlflowRunScope, MlflowRunWriter, MlflowRunWriterOptions, MlflowRunWriterStats, OutputCapture,
+    QueueFullPolicy, Reduction, StepMode, TrackProgress, Tracked, HEARTBEAT_TAG, OUTPUT_ARTIFACT,
+    PANIC_MESSAGE_TAG, PROGRESS_TAG,
+};
+pub use mlflow_tracer::{MlflowSpan, MlflowTracer};
+#[cfg(feature = "image")]
+pub use run_image::Image;
 
+pub mod build_info;
 pub mod client;
 pub mod data;
+#[cfg(feature = "opentelemetry")]
+pub mod opentelemetry;
+pub mod run_context;
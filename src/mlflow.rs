@@ -1,7 +1,19 @@
+use std::{env, path::Path, time::Duration};
+
 use crate::client::MlflowClient;
-use crate::data::{CreateExperimentOptions, SearchExperimentsOptions};
+use crate::data::{
+    CreateExperimentOptions, Run, RunStatus, SearchExperimentsOptions, SearchRunsOptions,
+    Timestamp, UpdateRunOptions,
+};
+use crate::mlflow_run_writer::replay_journals;
 use crate::utils::none_if_not_exist;
-use crate::{MlflowExperiment, Result};
+use crate::{
+    MlflowExperiment, MlflowLoggedModel, MlflowRun, MlflowRunWriter, MlflowRunWriterOptions,
+    Result, HEARTBEAT_TAG,
+};
+
+/// Environment variable that specifies the ID of the Run to resume.
+const MLFLOW_RUN_ID: &str = "MLFLOW_RUN_ID";
 
 /// Represents the [MLflow Tracking Server] to which requests are sent.
 ///
@@ -72,6 +84,120 @@ impl Mlflow {
         })
     }
 
+    /// Get a LoggedModel by its ID.
+    pub fn logged_model(&self, id: &str) -> Result<Option<MlflowLoggedModel>> {
+        none_if_not_exist(self.client.get_logged_model(id), |r| {
+            Ok(MlflowLoggedModel::new(&self.client, r.model))
+        })
+    }
+
+    /// Reopen an existing Run and return its [`MlflowRunWriter`].
+    ///
+    /// The status of the Run is set back to Running, and its end time is cleared.
+    /// Use [`MlflowRunWriter::last_step`] to continue logging metrics from the last logged step.
+    pub fn resume_run(&self, run_id: &str) -> Result<MlflowRunWriter> {
+        self.resume_run_with_writer_options(run_id, MlflowRunWriterOptions::default())
+    }
+
+    /// Reopen an existing Run and return its [`MlflowRunWriter`] with the specified options.
+    pub fn resume_run_with_writer_options(
+        &self,
+        run_id: &str,
+        writer_options: MlflowRunWriterOptions,
+    ) -> Result<MlflowRunWriter> {
+        let mut run = self.client.get_run(run_id)?.run;
+        let previous = UpdateRunOptions {
+            status: Some(run.info.status),
+            end_time: run.info.end_time,
+            ..Default::default()
+        };
+        // The API cannot unset the end time, so it is set to 0, which MLflow shows as no end time.
+        self.client.update_run(
+            run_id,
+            UpdateRunOptions {
+                status: Some(RunStatus::Running),
+                end_time: Some(Timestamp(0)),
+                ..Default::default()
+            },
+        )?;
+        run.info.status = RunStatus::Running;
+        run.info.end_time = None;
+        MlflowRun::new(&self.client, run)
+            .writer(writer_options)
+            .inspect_err(|_| {
+                // The Run may have been ended by the writer that failed to start, so restore the status it had.
+                let _ = self.client.update_run(run_id, previous);
+            })
+    }
+
+    /// Reopen the Run specified by the `MLFLOW_RUN_ID` environment variable.
+    ///
+    /// Returns `None` if the environment variable is not set.
+    pub fn resume_run_from_env(&self) -> Result<Option<MlflowRunWriter>> {
+        self.resume_run_from(env::var(MLFLOW_RUN_ID).ok().as_deref())
+    }
+
+    /// Reopen the Run with the ID `run_id`, typically read from the `MLFLOW_RUN_ID` environment variable.
+    ///
+    /// Returns `None` if `run_id` is `None` or empty.
+    pub fn resume_run_from(&self, run_id: Option<&str>) -> Result<Option<MlflowRunWriter>> {
+        match run_id {
+            Some(run_id) if !run_id.is_empty() => Ok(Some(self.resume_run(run_id)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Send the logs left in the write-ahead journals in `dir` and remove the journals.
+    ///
+    /// Journals are left behind when a [`MlflowRunWriter`](crate::MlflowRunWriter) with
+    /// [`journal_dir`](crate::MlflowRunWriterOptions::journal_dir) could not send all logs before the process exited.
+    /// Call this at process start to send them.
+    ///
+    /// Returns the IDs of the replayed runs.
+    pub fn replay_journals(&self, dir: impl AsRef<Path>) -> Result<Vec<String>> {
+        replay_journals(&self.client, dir.as_ref())
+    }
+
+    /// Get the Runs in the experiment that are Running but have shown no activity for longer than `max_age`.
+    ///
+    /// The last activity of a Run is the latest of its [`HEARTBEAT_TAG`] tag, the timestamps of its latest metrics
+    /// and its start time.
+    /// Such Runs are typically left behind by processes that were killed without ending their Runs.
+    pub fn find_stale_runs(
+        &self,
+        experiment_id: &str,
+        max_age: Duration,
+    ) -> Result<Vec<MlflowRun>> {
+        let options = SearchRunsOptions {
+            filter: "attributes.status = 'RUNNING'",
+            ..Default::default()
+        };
+        let now = Timestamp::now().0;
+        let max_age = max_age.as_millis() as i64;
+        let mut runs = MlflowRun::search(&self.client, experiment_id, options)?;
+        runs.retain(|run| now - last_activity(run.data()).0 > max_age);
+        Ok(runs)
+    }
+
+    /// Mark the Runs found by [`find_stale_runs`](Self::find_stale_runs) as Failed.
+    ///
+    /// Returns the Runs that were marked.
+    pub fn fail_stale_runs(
+        &self,
+        experiment_id: &str,
+        max_age: Duration,
+    ) -> Result<Vec<MlflowRun>> {
+        let runs = self.find_stale_runs(experiment_id, max_age)?;
+        for run in &runs {
+            run.update(UpdateRunOptions {
+                status: Some(RunStatus::Failed),
+                end_time: Some(Timestamp::now()),
+                ..Default::default()
+            })?;
+        }
+        Ok(runs)
+    }
+
     /// Create a new experiment.
     pub fn create_experiment(
         &self,
@@ -95,3 +221,16 @@ impl Mlflow {
         self.create_experiment(name, options)
     }
 }
+
+fn last_activity(run: &Run) -> Timestamp {
+    let heartbeat = run
+        .tag(HEARTBEAT_TAG)
+        .and_then(|s| s.parse().ok())
+        .map(Timestamp);
+    run.data
+        .metrics
+        .iter()
+        .map(|m| m.timestamp)
+        .chain(heartbeat)
+        .fold(run.info.start_time, Timestamp::max)
+}
@@ -1,7 +1,13 @@
 use crate::client::MlflowClient;
-use crate::data::{CreateRunOptions, Experiment, SearchRunsOptions, Timestamp};
+use crate::data::{
+    CreateLoggedModelOptions, CreateRunOptions, Experiment, SearchLoggedModelsOptions,
+    SearchRunsOptions, SearchTracesOptions, Timestamp, TraceInfo,
+};
+use crate::run_context;
 use crate::utils::none_if_not_exist;
-use crate::{MlflowRun, MlflowRunWriter, Result};
+use crate::{
+    MlflowLoggedModel, MlflowRun, MlflowRunWriter, MlflowRunWriterOptions, MlflowTracer, Result,
+};
 
 /// Represents a [Experiment](https://mlflow.org/docs/latest/tracking.html#experiments).
 #[derive(Debug, Clone)]
@@ -58,24 +64,7 @@ impl MlflowExperiment {
 
     /// Get all runs in this experiment that match the specified search options.
     pub fn runs_with(&self, options: SearchRunsOptions) -> Result<Vec<MlflowRun>> {
-        let mut results = Vec::new();
-        let mut page_token = None;
-        loop {
-            let response = self.client.search_runs(
-                &[self.id()],
-                options,
-                MlflowClient::SEARCH_RUNS_MAX_RESULTS_SUPPORTED,
-                page_token.as_deref(),
-            )?;
-            for run in response.runs {
-                results.push(MlflowRun::new(&self.client, run));
-            }
-            page_token = response.next_page_token;
-            if page_token.is_none() {
-                break;
-            }
-        }
-        Ok(results)
+        MlflowRun::search(&self.client, self.id(), options)
     }
 
     /// Get a run by its ID.
@@ -102,14 +91,133 @@ impl MlflowExperiment {
     /// Creates a Run with the specified options and returns its [`MlflowRunWriter`].
     ///
     /// `options.start_time` is set to the current time if not specified.
-    pub fn start_run_with(
+    /// Tags from the [run context](crate::run_context) are added to `options.tags`.
+    pub fn start_run_with(&self, name: &str, options: CreateRunOptions) -> Result<MlflowRunWriter> {
+        self.start_run_with_writer_options(name, options, MlflowRunWriterOptions::default())
+    }
+
+    /// Creates a Run with the specified options and returns its [`MlflowRunWriter`] configured with `writer_options`.
+    ///
+    /// `options.start_time` is set to the current time if not specified.
+    /// Tags from the [run context](crate::run_context) are added to `options.tags`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    /// use mlflow_client::{MlflowRunWriterOptions, QueueFullPolicy};
+    ///
+    /// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+    /// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+    /// let mut run = experiment.start_run_with_writer_options(
+    ///     "run_name",
+    ///     Default::default(),
+    ///     MlflowRunWriterOptions {
+    ///         flush_interval: Duration::from_secs(1),
+    ///         max_queue_size: Some(10000),
+    ///         queue_full_policy: QueueFullPolicy::DropOldest,
+    ///         ..Default::default()
+    ///     },
+    /// )?;
+    /// run.log_metric("loss", 0.5, Some(0))?;
+    /// run.flush()?;
+    /// run.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn start_run_with_writer_options(
         &self,
         name: &str,
         mut options: CreateRunOptions,
+        writer_options: MlflowRunWriterOptions,
     ) -> Result<MlflowRunWriter> {
         if options.start_time.is_none() {
             options.start_time = Some(Timestamp::now());
         }
-        Ok(self.create_run(name, options)?.writer())
+        let tags = run_context::resolve_tags(options.tags);
+        let options = CreateRunOptions {
+            tags: &tags,
+            ..options
+        };
+        self.create_run(name, options)?.writer(writer_options)
+    }
+
+    /// Get all LoggedModels in this experiment.
+    pub fn logged_models(&self) -> Result<Vec<MlflowLoggedModel>> {
+        self.logged_models_with(SearchLoggedModelsOptions::default())
+    }
+
+    /// Get all LoggedModels in this experiment that match the specified search options.
+    pub fn logged_models_with(
+        &self,
+        options: SearchLoggedModelsOptions,
+    ) -> Result<Vec<MlflowLoggedModel>> {
+        let mut results = Vec::new();
+        let mut page_token = None;
+        loop {
+            let response = self.client.search_logged_models(
+                &[self.id()],
+                options,
+                MlflowClient::SEARCH_LOGGED_MODELS_MAX_RESULTS_SUPPORTED,
+                page_token.as_deref(),
+            )?;
+            for model in response.models {
+                results.push(MlflowLoggedModel::new(&self.client, model));
+            }
+            page_token = response.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Create a new LoggedModel.
+    ///
+    /// Use [`MlflowRun::create_logged_model`] to create a LoggedModel produced by a run.
+    pub fn create_logged_model(
+        &self,
+        name: &str,
+        options: CreateLoggedModelOptions,
+    ) -> Result<MlflowLoggedModel> {
+        let r = self.client.create_logged_model(self.id(), name, options)?;
+        Ok(MlflowLoggedModel::new(&self.client, r.model))
+    }
+
+    /// Returns a [`MlflowTracer`] that records traces in this experiment.
+    pub fn tracer(&self) -> MlflowTracer {
+        MlflowTracer::new(&self.client, self.id())
+    }
+
+    /// Create an OpenTelemetry span exporter that sends traces to this experiment.
+    #[cfg(feature = "opentelemetry")]
+    pub fn span_exporter(&self) -> crate::opentelemetry::MlflowSpanExporter {
+        crate::opentelemetry::MlflowSpanExporter::new(self.client.clone(), self.id())
+    }
+
+    /// Get all traces in this experiment.
+    pub fn traces(&self) -> Result<Vec<TraceInfo>> {
+        self.traces_with(SearchTracesOptions::default())
+    }
+
+    /// Get all traces in this experiment that match the specified search options.
+    pub fn traces_with(&self, options: SearchTracesOptions) -> Result<Vec<TraceInfo>> {
+        let mut results = Vec::new();
+        let mut page_token = None;
+        loop {
+            let response = self.client.search_traces(
+                &[self.id()],
+                options,
+                MlflowClient::SEARCH_TRACES_MAX_RESULTS_SUPPORTED,
+                page_token.as_deref(),
+            )?;
+            results.extend(response.traces);
+            page_token = response.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        Ok(results)
     }
 }
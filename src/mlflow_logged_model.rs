@@ -0,0 +1,103 @@
+use serde::Serialize;
+
+use crate::client::MlflowClient;
+use crate::data::{LoggedModel, LoggedModelParameter, LoggedModelStatus, LoggedModelTag};
+use crate::utils::build_params;
+use crate::Result;
+
+/// Represents a [LoggedModel](https://mlflow.org/docs/latest/genai/concepts/logged-model/).
+///
+/// Requires MLflow 3.0 or later.
+#[derive(Debug, Clone)]
+pub struct MlflowLoggedModel {
+    client: MlflowClient,
+    data: LoggedModel,
+}
+
+impl MlflowLoggedModel {
+    pub(crate) fn new(client: &MlflowClient, data: LoggedModel) -> MlflowLoggedModel {
+        MlflowLoggedModel {
+            client: client.clone(),
+            data,
+        }
+    }
+    pub fn id(&self) -> &str {
+        &self.data.info.model_id
+    }
+    pub fn name(&self) -> &str {
+        &self.data.info.name
+    }
+    pub fn data(&self) -> &LoggedModel {
+        &self.data
+    }
+
+    /// Retrieves the information about this LoggedModel from the server.
+    ///
+    /// Returns a new `MlflowLoggedModel` with the updated information.
+    /// The information in `self` is not modified.
+    pub fn reload(&self) -> Result<Self> {
+        Ok(MlflowLoggedModel::new(
+            &self.client,
+            self.client.get_logged_model(self.id())?.model,
+        ))
+    }
+
+    /// Sets the final status of this LoggedModel.
+    ///
+    /// The information in `self` is not modified.
+    /// Use [`reload`](Self::reload) to get the updated information.
+    pub fn finalize(&self, status: LoggedModelStatus) -> Result<()> {
+        self.client.finalize_logged_model(self.id(), status)?;
+        Ok(())
+    }
+
+    pub fn delete(&self) -> Result<()> {
+        self.client.delete_logged_model(self.id())?;
+        Ok(())
+    }
+
+    pub fn set_tag(&self, key: &str, value: &str) -> Result<()> {
+        self.set_tags(&[LoggedModelTag {
+            key: key.to_string(),
+            value: value.to_string(),
+        }])
+    }
+    pub fn set_tags(&self, tags: &[LoggedModelTag]) -> Result<()> {
+        self.client.set_logged_model_tags(self.id(), tags)?;
+        Ok(())
+    }
+    pub fn delete_tag(&self, key: &str) -> Result<()> {
+        self.client.delete_logged_model_tag(self.id(), key)?;
+        Ok(())
+    }
+
+    /// Logs a single parameter.
+    pub fn log_param(&self, key: &str, value: &str) -> Result<()> {
+        self.client.log_logged_model_params(
+            self.id(),
+            &[LoggedModelParameter {
+                key: key.to_string(),
+                value: value.to_string(),
+            }],
+        )?;
+        Ok(())
+    }
+
+    /// Logs multiple parameters.
+    ///
+    /// See [`MlflowRunWriter::log_params`](crate::MlflowRunWriter::log_params) for reference.
+    pub fn log_params(&self, key: &str, values: impl Serialize) -> Result<()> {
+        let values = serde_json::to_value(values)?;
+        let mut params = Vec::new();
+        build_params(key, &values, &mut params)?;
+        let params = params
+            .into_iter()
+            .map(|p| LoggedModelParameter {
+                key: p.key,
+                value: p.value,
+            })
+            .collect::<Vec<_>>();
+        self.client.log_logged_model_params(self.id(), &params)?;
+        Ok(())
+    }
+}
@@ -1,9 +1,16 @@
-use serde::Serialize;
+use std::path::Path;
 
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint::{self, Checkpoint, MetricGoal};
 use crate::client::MlflowClient;
-use crate::data::{Metric, Param, Run, RunTag, Timestamp, UpdateRunOptions};
-use crate::utils::build_params;
-use crate::{MlflowRunWriter, Result};
+use crate::data::{
+    CreateLoggedModelOptions, CreateRunOptions, Metric, Param, Run, RunTag, SearchRunsOptions,
+    Timestamp, UpdateRunOptions,
+};
+use crate::run_context;
+use crate::utils::{build_params, none_if_not_exist, to_yaml};
+use crate::{Error, MlflowLoggedModel, MlflowRunWriter, MlflowRunWriterOptions, Result};
 
 /// Represents a [Run](https://mlflow.org/docs/latest/tracking.html#runs).
 #[derive(Debug, Clone)]
@@ -30,6 +37,31 @@ impl MlflowRun {
         &self.data
     }
 
+    /// Returns the ID of the parent Run if this is a nested Run.
+    pub fn parent_id(&self) -> Option<&str> {
+        self.data.tag(Run::PARENT_RUN_ID_TAG)
+    }
+
+    /// Get the parent Run if this is a nested Run.
+    pub fn parent(&self) -> Result<Option<MlflowRun>> {
+        let Some(parent_id) = self.parent_id() else {
+            return Ok(None);
+        };
+        none_if_not_exist(self.client.get_run(parent_id), |r| {
+            Ok(MlflowRun::new(&self.client, r.run))
+        })
+    }
+
+    /// Get the active Runs nested directly under this Run.
+    pub fn children(&self) -> Result<Vec<MlflowRun>> {
+        let filter = format!("tags.`{}` = '{}'", Run::PARENT_RUN_ID_TAG, self.id());
+        let options = SearchRunsOptions {
+            filter: &filter,
+            ..Default::default()
+        };
+        Self::search(&self.client, &self.data.info.experiment_id, options)
+    }
+
     /// Retrieves the information aboutthis Run from the server.
     ///
     /// Returns a new `MlflowRun` with the updated information.
@@ -112,6 +144,9 @@ impl MlflowRun {
                 value: *value,
                 timestamp,
                 step,
+                model_id: None,
+                dataset_name: None,
+                dataset_digest: None,
             })
             .collect::<Vec<_>>();
         self.log_batch(&metrics, &[], &[])
@@ -158,6 +193,154 @@ impl MlflowRun {
         Ok(())
     }
 
+    /// Uploads `data` as an artifact at `path` relative to the artifact root of this Run.
+    pub fn log_artifact(&self, path: &str, data: Vec<u8>) -> Result<()> {
+        self.client
+            .upload_artifact(&self.data.info.artifact_uri, path, data)
+    }
+
+    /// Downloads the artifact at `path` relative to the artifact root of this Run.
+    pub fn download_artifact(&self, path: &str) -> Result<Vec<u8>> {
+        self.client
+            .download_artifact(&self.data.info.artifact_uri, path)
+    }
+
+    /// Uploads `value` as a JSON or YAML artifact at `path` relative to the artifact root of this Run.
+    ///
+    /// The format is YAML if `path` ends with `.yaml` or `.yml`, otherwise JSON.
+    /// Writing YAML requires the `yaml` feature, and returns an error without it.
+    pub fn log_dict(&self, value: &impl Serialize, path: &str) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        let data = if path.ends_with(".yaml") || path.ends_with(".yml") {
+            to_yaml(&value)?
+        } else {
+            serde_json::to_vec_pretty(&value)?
+        };
+        self.log_artifact(path, data)
+    }
+
+    /// Uploads a table as a JSON artifact at `path` relative to the artifact root of this Run.
+    ///
+    /// The table is written in the format of `log_table` of the MLflow Python client,
+    /// and listed in the [`Run::LOGGED_ARTIFACTS_TAG`] tag so that the MLflow UI shows it in the evaluation view.
+    /// If a table already exists at `path`, `rows` are appended to it.
+    /// Columns missing from either table are filled with `null`.
+    pub fn log_table(
+        &self,
+        columns: &[&str],
+        rows: &[Vec<serde_json::Value>],
+        path: &str,
+    ) -> Result<()> {
+        if !path.ends_with(".json") {
+            return Err(Error::from_message(format!(
+                "Table artifact path must end with `.json`: {path}"
+            )));
+        }
+        if let Some(row) = rows.iter().find(|row| row.len() != columns.len()) {
+            return Err(Error::from_message(format!(
+                "Row has {} values but the table has {} columns",
+                row.len(),
+                columns.len()
+            )));
+        }
+        let existing = none_if_not_exist(self.download_artifact(path), |data| {
+            Ok(serde_json::from_slice::<Table>(&data)?)
+        })?;
+        let mut table = existing.unwrap_or_default();
+        for column in columns {
+            if !table.columns.iter().any(|c| c == column) {
+                table.columns.push(column.to_string());
+                for row in &mut table.data {
+                    row.push(serde_json::Value::Null);
+                }
+            }
+        }
+        for row in rows {
+            let mut values = vec![serde_json::Value::Null; table.columns.len()];
+            for (column, value) in columns.iter().zip(row) {
+                let i = table.columns.iter().position(|c| c == column).unwrap();
+                values[i] = value.clone();
+            }
+            table.data.push(values);
+        }
+        self.log_artifact(path, serde_json::to_vec(&table)?)?;
+
+        let run = self.client.get_run(self.id())?.run;
+        let mut logged = match run.tag(Run::LOGGED_ARTIFACTS_TAG) {
+            Some(s) => serde_json::from_str::<Vec<LoggedArtifact>>(s)?,
+            None => Vec::new(),
+        };
+        let artifact = LoggedArtifact {
+            path: path.to_string(),
+            ty: "table".to_string(),
+        };
+        if !logged.contains(&artifact) {
+            logged.push(artifact);
+            self.set_tag(Run::LOGGED_ARTIFACTS_TAG, &serde_json::to_string(&logged)?)?;
+        }
+        Ok(())
+    }
+
+    /// Uploads an image for `key` at `step` so that it appears in the image gallery of the MLflow UI.
+    ///
+    /// The image is stored as PNG with a WebP thumbnail under `images/`
+    /// in the same layout as `log_image` of the MLflow Python client. The step defaults to 0.
+    #[cfg(feature = "image")]
+    pub fn log_image(&self, image: crate::Image, key: &str, step: Option<i64>) -> Result<()> {
+        crate::run_image::log_image(self, image, key, step.unwrap_or(0))
+    }
+
+    /// Deletes the artifact at `path` relative to the artifact root of this Run.
+    pub fn delete_artifact(&self, path: &str) -> Result<()> {
+        self.client
+            .delete_artifact(&self.data.info.artifact_uri, path)
+    }
+
+    /// Get the checkpoints of this Run in ascending order of step.
+    ///
+    /// Checkpoints are saved by [`MlflowRunWriter::save_checkpoint`].
+    pub fn checkpoints(&self) -> Result<Vec<Checkpoint>> {
+        checkpoint::checkpoints(self)
+    }
+
+    /// Downloads the files of `checkpoint` into `dir`.
+    ///
+    /// Returns an error if a file name of `checkpoint` is not a relative path inside `dir`, such as `../model.bin`.
+    pub fn download_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+        dir: impl AsRef<Path>,
+    ) -> Result<()> {
+        checkpoint::download(self, checkpoint, dir.as_ref())
+    }
+
+    /// Downloads the checkpoint with the largest step into `dir` and returns it.
+    ///
+    /// Returns `None` if this Run has no checkpoints.
+    pub fn latest_checkpoint(&self, dir: impl AsRef<Path>) -> Result<Option<Checkpoint>> {
+        let Some(c) = self.checkpoints()?.pop() else {
+            return Ok(None);
+        };
+        self.download_checkpoint(&c, dir)?;
+        Ok(Some(c))
+    }
+
+    /// Downloads the checkpoint with the best value of `metric` at the time it was saved into `dir` and returns it.
+    ///
+    /// Returns `None` if no checkpoint has a value of `metric`.
+    pub fn best_checkpoint(
+        &self,
+        metric: &str,
+        goal: MetricGoal,
+        dir: impl AsRef<Path>,
+    ) -> Result<Option<Checkpoint>> {
+        let Some(c) = checkpoint::best(self.checkpoints()?, metric, goal) else {
+            return Ok(None);
+        };
+        self.download_checkpoint(&c, dir)?;
+        Ok(Some(c))
+    }
+
     /// Retrieves the entire history of metrics for the specified key.
     pub fn metric_history(&self, key: &str) -> Result<Vec<Metric>> {
         let mut results = Vec::new();
@@ -174,7 +357,84 @@ impl MlflowRun {
         }
         Ok(results)
     }
-    pub(crate) fn writer(&self) -> MlflowRunWriter {
-        MlflowRunWriter::new(self.clone())
+    /// Create a new LoggedModel whose source is this run.
+    ///
+    /// `options.source_run_id` is set to the ID of this run.
+    pub fn create_logged_model(
+        &self,
+        name: &str,
+        options: CreateLoggedModelOptions,
+    ) -> Result<MlflowLoggedModel> {
+        let options = CreateLoggedModelOptions {
+            source_run_id: Some(self.id()),
+            ..options
+        };
+        let r = self
+            .client
+            .create_logged_model(&self.data.info.experiment_id, name, options)?;
+        Ok(MlflowLoggedModel::new(&self.client, r.model))
     }
+
+    pub(crate) fn search(
+        client: &MlflowClient,
+        experiment_id: &str,
+        options: SearchRunsOptions,
+    ) -> Result<Vec<MlflowRun>> {
+        let mut results = Vec::new();
+        let mut page_token = None;
+        loop {
+            let response = client.search_runs(
+                &[experiment_id],
+                options,
+                MlflowClient::SEARCH_RUNS_MAX_RESULTS_SUPPORTED,
+                page_token.as_deref(),
+            )?;
+            for run in response.runs {
+                results.push(MlflowRun::new(client, run));
+            }
+            page_token = response.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Creates a Run nested under this Run and returns its [`MlflowRunWriter`].
+    pub(crate) fn start_child_run(
+        &self,
+        name: &str,
+        writer_options: MlflowRunWriterOptions,
+    ) -> Result<MlflowRunWriter> {
+        let tags = run_context::resolve_tags(&[RunTag {
+            key: Run::PARENT_RUN_ID_TAG.to_string(),
+            value: self.id().to_string(),
+        }]);
+        let options = CreateRunOptions {
+            start_time: Some(Timestamp::now()),
+            tags: &tags,
+        };
+        let r = self
+            .client
+            .create_run(&self.data.info.experiment_id, name, options)?;
+        MlflowRun::new(&self.client, r.run).writer(writer_options)
+    }
+
+    pub(crate) fn writer(&self, options: MlflowRunWriterOptions) -> Result<MlflowRunWriter> {
+        MlflowRunWriter::new(self.clone(), options)
+    }
+}
+
+/// Table in the `split` orientation of pandas, as written by `log_table` of the MLflow Python client.
+#[derive(Serialize, Deserialize, Default)]
+struct Table {
+    columns: Vec<String>,
+    data: Vec<Vec<serde_json::Value>>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct LoggedArtifact {
+    path: String,
+    #[serde(rename = "type")]
+    ty: String,
 }
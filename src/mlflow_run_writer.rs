@@ -1,22 +1,275 @@
 use std::{
+    collections::HashMap,
+    fs,
     mem::take,
-    sync::{Arc, Mutex},
-    thread::{spawn, JoinHandle},
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread::{self, sleep, spawn, JoinHandle},
+    time::{Duration, Instant},
 };
 
 use serde::Serialize;
 
 use crate::{
-    data::{Metric, RunStatus, Timestamp, UpdateRunOptions},
+    build_info::BuildInfo,
+    checkpoint::{self, Checkpoint, CheckpointPolicy},
+    client::MlflowClient,
+    data::{Dataset, Metric, Param, RunStatus, RunTag, Timestamp, UpdateRunOptions},
+    utils::build_params,
     Error, MlflowRun, Result,
 };
 
+use aggregation::Aggregator;
+use journal::{Journal, Record};
+
+mod aggregation;
+mod journal;
+mod output;
+mod progress;
+mod scope;
+mod system_metrics;
+mod termination;
+
+pub use aggregation::{Aggregation, AggregationWindow, Reduction};
+pub use output::{OutputCapture, OUTPUT_ARTIFACT};
+pub use progress::{MlflowRunProgress, TrackProgress, Tracked, PROGRESS_TAG};
+pub use scope::MlflowRunScope;
+
+/// Tag key for the panic message recorded when a [`MlflowRunWriter`] is dropped during a panic.
+///
+/// See [`MlflowRunWriterOptions::capture_panic_message`].
+pub const PANIC_MESSAGE_TAG: &str = "mlflow_client.panic_message";
+
+/// Tag key for the time of the last heartbeat of a [`MlflowRunWriter`], in Unix milliseconds.
+///
+/// See [`MlflowRunWriterOptions::heartbeat_interval`].
+pub const HEARTBEAT_TAG: &str = "mlflow_client.heartbeat";
+
+/// Number of appended records after which the journal is rewritten to contain only unsent records.
+///
+/// Sent records are acknowledged in the journal as soon as the server responds, so this only bounds the file size.
+const JOURNAL_COMPACTION_THRESHOLD: usize = 10000;
+
+/// Maximum wait time between attempts to send journaled logs while the server is unreachable.
+const MAX_OUTAGE_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Behavior when logging metrics to a [`MlflowRunWriter`] whose queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueueFullPolicy {
+    /// Wait until the queue has room.
+    ///
+    /// While the server is unreachable with [`journal_dir`](MlflowRunWriterOptions::journal_dir) specified,
+    /// logging does not wait because the queued metrics are kept in the journal.
+    #[default]
+    Block,
+    /// Discard the oldest queued metrics to make room.
+    DropOldest,
+    /// Return an error without logging the metrics.
+    Error,
+}
+
+/// Step assigned to metrics logged by a [`MlflowRunWriter`] without a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StepMode {
+    /// Log without a step. The server uses step 0.
+    #[default]
+    None,
+    /// Use the step following the last step logged for the same key, starting from 0.
+    PerKey,
+    /// Use the global step of the writer.
+    ///
+    /// See [`MlflowRunWriter::advance_step`].
+    Global,
+}
+
+/// Behavior of a [`MlflowRunWriter`] when sending logs fails even after retries.
+///
+/// In both cases, the error is returned from the next method call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Stop sending logs.
+    ///
+    /// Pending logs are dropped and subsequent logging returns an error.
+    /// The status of the run is still updated when the run is ended.
+    #[default]
+    FailFast,
+    /// Count the failed logs and continue sending subsequent logs.
+    BestEffort,
+}
+
+/// Counts of logs processed by a [`MlflowRunWriter`].
+///
+/// Each metric, parameter and tag is counted as one record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MlflowRunWriterStats {
+    /// Number of records sent successfully.
+    pub sent: u64,
+    /// Number of records discarded without being sent,
+    /// by [`QueueFullPolicy::DropOldest`] or [`ErrorPolicy::FailFast`].
+    pub dropped: u64,
+    /// Number of records whose request failed after all retries.
+    pub failed: u64,
+}
+
+/// Options for [`MlflowRunWriter`].
+///
+/// Use with [`MlflowExperiment::start_run_with_writer_options`](crate::MlflowExperiment::start_run_with_writer_options).
+#[derive(Debug, Clone)]
+pub struct MlflowRunWriterOptions {
+    /// Maximum number of metrics, parameters and tags sent in a single request.
+    ///
+    /// The limits of the `log-batch` API are applied as well.
+    pub max_batch_size: usize,
+    /// Minimum interval between requests.
+    ///
+    /// Logs written during the interval are combined into a single request,
+    /// unless `max_batch_size` is reached, the run is ended or a flush is requested.
+    pub flush_interval: Duration,
+    /// Maximum number of metrics waiting to be sent. `None` means unbounded.
+    pub max_queue_size: Option<usize>,
+    /// Behavior when logging metrics while the queue is full.
+    pub queue_full_policy: QueueFullPolicy,
+    /// Maximum number of retries for a request that failed with a transient error.
+    pub max_retries: u32,
+    /// Wait time before the first retry. The wait time doubles with each retry.
+    pub retry_interval: Duration,
+    /// Behavior when a request fails even after retries.
+    pub error_policy: ErrorPolicy,
+    /// Directory of the write-ahead journal. `None` disables the journal.
+    ///
+    /// If specified, every metric, parameter, tag and status change is appended to `<journal_dir>/<run_id>.jsonl`
+    /// before it is sent, and removed from the file once the server acknowledges it.
+    ///
+    /// While the server is unreachable, logs are kept and retried until the server responds again,
+    /// instead of being counted as failed.
+    /// If the run is ended or the process exits before the logs are sent, the journal is left in place.
+    /// It is replayed when a writer for the same run is created with the same `journal_dir`,
+    /// or by [`Mlflow::replay_journals`](crate::Mlflow::replay_journals).
+    pub journal_dir: Option<PathBuf>,
+    /// If `true`, a process-wide handler for SIGINT, SIGTERM and SIGHUP (Ctrl-C and console close on Windows) is installed.
+    ///
+    /// On a signal, the runs of all writers created with this option are ended with [`RunStatus::Killed`],
+    /// and pending logs are sent for up to `signal_flush_timeout`.
+    /// Then the process is terminated by the signal with its default action, so the exit code is 128 + the signal number.
+    /// On Windows, the process exits with code 130.
+    ///
+    /// The handler replaces the handlers previously installed for these signals.
+    /// On Windows, creating the writer fails if another handler was installed with the `ctrlc` crate.
+    ///
+    /// This option is available when the `signals` feature is enabled.
+    #[cfg(feature = "signals")]
+    pub handle_signals: bool,
+    /// Maximum wait time for sending pending logs after a signal when `handle_signals` is `true`.
+    #[cfg(feature = "signals")]
+    pub signal_flush_timeout: Duration,
+    /// If `true`, the panic message is recorded in the [`PANIC_MESSAGE_TAG`] tag when the writer is dropped during a panic.
+    ///
+    /// To obtain the message, a process-wide panic hook is installed when the first writer with this option is created.
+    /// The hook records the message of each panic and then calls the hook that was installed before it.
+    pub capture_panic_message: bool,
+    /// Interval of sampling system metrics. `None` disables sampling.
+    ///
+    /// If specified, CPU, memory, disk and network usage are read from `/proc` and cgroup
+    /// and logged as metrics prefixed with `system/`, with steps numbered from 0, in the same way as the MLflow Python client.
+    /// CPU and memory usage are relative to the cgroup limits if the process runs in a cgroup with limits.
+    /// Sampling stops when the run is ended. Nothing is logged on platforms other than Linux.
+    pub system_metrics_interval: Option<Duration>,
+    /// Step used for metrics logged without a step.
+    pub step_mode: StepMode,
+    /// Aggregations applied to the metrics with the specified keys before they are sent.
+    ///
+    /// See [`Aggregation`] for details.
+    pub aggregations: HashMap<String, Aggregation>,
+    /// Interval of updating the [`HEARTBEAT_TAG`] tag while the run is running. `None` disables the heartbeat.
+    ///
+    /// The heartbeat is sent apart from other logs: it is not written to the journal and not counted in [`MlflowRunWriterStats`].
+    ///
+    /// Runs whose heartbeat stopped can be found by [`Mlflow::find_stale_runs`](crate::Mlflow::find_stale_runs).
+    pub heartbeat_interval: Option<Duration>,
+    /// Minimum interval of logging the progress of [`MlflowRunProgress`]. The default is 10 seconds.
+    pub progress_interval: Duration,
+    /// Capture of the console output uploaded as artifacts. `None` disables the capture.
+    ///
+    /// See [`OutputCapture`] for details.
+    pub output_capture: Option<OutputCapture>,
+    /// Checkpoints kept by [`MlflowRunWriter::save_checkpoint`]. The default keeps all checkpoints.
+    pub checkpoint_policy: CheckpointPolicy,
+}
+impl Default for MlflowRunWriterOptions {
+    fn default() -> Self {
+        Self {
+            max_batch_size: MlflowClient::LOG_BATCH_MAX_TOTAL,
+            flush_interval: Duration::ZERO,
+            max_queue_size: None,
+            queue_full_policy: QueueFullPolicy::Block,
+            max_retries: 3,
+            retry_interval: Duration::from_millis(500),
+            error_policy: ErrorPolicy::FailFast,
+            journal_dir: None,
+            #[cfg(feature = "signals")]
+            handle_signals: false,
+            #[cfg(feature = "signals")]
+            signal_flush_timeout: Duration::from_secs(5),
+            capture_panic_message: false,
+            system_metrics_interval: None,
+            step_mode: StepMode::None,
+            aggregations: HashMap::new(),
+            heartbeat_interval: None,
+            progress_interval: Duration::from_secs(10),
+            output_capture: None,
+            checkpoint_policy: CheckpointPolicy::default(),
+        }
+    }
+}
+
+/// Number of entries of each kind, used to track which logs have been processed.
+#[derive(Clone, Copy, Default)]
+struct Progress {
+    metrics: u64,
+    params: u64,
+    tags: u64,
+}
+impl Progress {
+    fn covers(&self, target: &Progress) -> bool {
+        self.metrics >= target.metrics && self.params >= target.params && self.tags >= target.tags
+    }
+}
+
+/// Pending logs are paired with their sequence numbers in the journal, which are 0 if the journal is disabled.
 struct Data {
-    metrics: Vec<Metric>,
+    metrics: Vec<(u64, Metric)>,
+    params: Vec<(u64, Param)>,
+    tags: Vec<(u64, RunTag)>,
+    param_values: HashMap<String, String>,
+    model_id: Option<String>,
     error: Option<Error>,
     status: RunStatus,
     end_time: Option<Timestamp>,
     task: Option<JoinHandle<()>>,
+    sampler: Option<JoinHandle<()>>,
+    capture: Option<JoinHandle<()>>,
+    enqueued: Progress,
+    done: Progress,
+    flush_requests: usize,
+    blocked: usize,
+    stopped: bool,
+    stats: MlflowRunWriterStats,
+    last_send: Option<Instant>,
+    journal: Option<Journal>,
+    /// Set if the journal contains logs that could not be sent and must be kept for a later replay.
+    journal_incomplete: bool,
+    /// Time of the next attempt to send journaled logs while the server is unreachable.
+    retry_at: Option<Instant>,
+    outage_backoff: Duration,
+    outage_error: Option<String>,
+    /// Set when the worker has ended the run and exited.
+    exited: bool,
+    global_step: i64,
+    /// Step following the last step logged for each metric key.
+    next_steps: HashMap<String, i64>,
+    /// Number of scopes ended for each scope name.
+    scope_steps: HashMap<String, i64>,
+    aggregators: HashMap<String, Aggregator>,
 }
 impl Data {
     fn take_error(&mut self) -> Result<()> {
@@ -35,6 +288,188 @@ impl Data {
             Ok(())
         }
     }
+    fn pending_len(&self) -> usize {
+        self.metrics.len() + self.params.len() + self.tags.len()
+    }
+
+    /// Returns how long to wait for more logs before sending the next request.
+    fn coalesce_wait(&self, options: &MlflowRunWriterOptions) -> Option<Duration> {
+        if self.status == RunStatus::Running {
+            let now = Instant::now();
+            if let Some(retry_at) = self.retry_at.filter(|&t| t > now) {
+                return Some(retry_at - now);
+            }
+        }
+        if self.status != RunStatus::Running
+            || self.flush_requests > 0
+            || self.blocked > 0
+            || self.pending_len() >= options.max_batch_size
+            || options
+                .max_queue_size
+                .is_some_and(|max| self.metrics.len() >= max)
+        {
+            return None;
+        }
+        let elapsed = self.last_send?.elapsed();
+        options
+            .flush_interval
+            .checked_sub(elapsed)
+            .filter(|wait| !wait.is_zero())
+    }
+
+    /// Takes pending logs that fit in a single `log-batch` request.
+    #[allow(clippy::type_complexity)]
+    fn take_batch(
+        &mut self,
+        max_batch_size: usize,
+    ) -> (Vec<(u64, Metric)>, Vec<(u64, Param)>, Vec<(u64, RunTag)>) {
+        let max = max_batch_size.clamp(1, MlflowClient::LOG_BATCH_MAX_TOTAL);
+        let params = take_front(
+            &mut self.params,
+            MlflowClient::LOG_BATCH_MAX_PARAMS.min(max),
+        );
+        let tags = take_front(
+            &mut self.tags,
+            MlflowClient::LOG_BATCH_MAX_TAGS.min(max - params.len()),
+        );
+        let metrics = take_front(
+            &mut self.metrics,
+            MlflowClient::LOG_BATCH_MAX_METRICS.min(max - params.len() - tags.len()),
+        );
+        (metrics, params, tags)
+    }
+    /// Removes all pending logs and returns their sequence numbers.
+    fn clear_pending(&mut self) -> Vec<u64> {
+        let metrics = take(&mut self.metrics);
+        let params = take(&mut self.params);
+        let tags = take(&mut self.tags);
+        self.done.metrics += metrics.len() as u64;
+        self.done.params += params.len() as u64;
+        self.done.tags += tags.len() as u64;
+        let metrics = metrics.into_iter().map(|(seq, _)| seq);
+        let params = params.into_iter().map(|(seq, _)| seq);
+        let tags = tags.into_iter().map(|(seq, _)| seq);
+        metrics.chain(params).chain(tags).collect()
+    }
+    fn set_tag(&mut self, tag: RunTag) -> Result<()> {
+        let seqs = self.append_journal([Record::Tag(tag.clone())])?;
+        self.set_tag_with_seq(seqs[0], tag);
+        Ok(())
+    }
+    /// Enqueues a tag that has already been appended to the journal, replacing a pending tag with the same key.
+    fn set_tag_with_seq(&mut self, seq: u64, tag: RunTag) {
+        let (replaced, tags) = take(&mut self.tags)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(_, t)| t.key == tag.key);
+        self.tags = tags;
+        self.done.tags += replaced.len() as u64;
+        // The replaced values must not be sent after the new value when the journal is replayed.
+        self.acknowledge(replaced.into_iter().map(|(seq, _)| seq));
+        self.tags.push((seq, tag));
+        self.enqueued.tags += 1;
+    }
+    fn enqueue_metrics(&mut self, metrics: Vec<Metric>) -> Result<()> {
+        let seqs = self.append_journal(metrics.iter().cloned().map(Record::Metric))?;
+        self.enqueued.metrics += metrics.len() as u64;
+        self.metrics.extend(seqs.into_iter().zip(metrics));
+        Ok(())
+    }
+
+    /// Replaces metrics with aggregations by the aggregated metrics of the closed windows.
+    fn aggregate(
+        &mut self,
+        aggregations: &HashMap<String, Aggregation>,
+        metrics: Vec<Metric>,
+    ) -> Vec<Metric> {
+        if aggregations.is_empty() {
+            return metrics;
+        }
+        let mut results = Vec::new();
+        for m in metrics {
+            if let Some(aggregation) = aggregations.get(&m.key) {
+                let aggregator = self
+                    .aggregators
+                    .entry(m.key.clone())
+                    .or_insert_with(Aggregator::new);
+                results.extend(aggregator.push(aggregation, m));
+            } else {
+                results.push(m);
+            }
+        }
+        results
+    }
+
+    /// Closes the windows of all aggregations and returns the aggregated metrics.
+    fn take_aggregated(&mut self, aggregations: &HashMap<String, Aggregation>) -> Vec<Metric> {
+        let mut keys = self.aggregators.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        let mut results = Vec::new();
+        for key in keys {
+            if let (Some(aggregation), Some(aggregator)) =
+                (aggregations.get(&key), self.aggregators.get_mut(&key))
+            {
+                results.extend(aggregator.take(aggregation));
+            }
+        }
+        results
+    }
+
+    /// Appends `records` to the journal and returns their sequence numbers.
+    fn append_journal(
+        &mut self,
+        records: impl IntoIterator<Item = Record, IntoIter: ExactSizeIterator>,
+    ) -> Result<Vec<u64>> {
+        if let Some(journal) = &mut self.journal {
+            journal.append(records)
+        } else {
+            Ok(vec![0; records.into_iter().len()])
+        }
+    }
+
+    /// Records in the journal that the logs with the sequence numbers `seqs` no longer need to be sent.
+    fn acknowledge(&mut self, seqs: impl IntoIterator<Item = u64>) {
+        if let Some(journal) = &mut self.journal {
+            let r = journal.acknowledge(seqs);
+            self.push_error(r.err());
+        }
+    }
+
+    /// Rewrites the journal to contain only the unsent logs, if it is empty or has grown large.
+    fn compact_journal(&mut self) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        if self.journal_incomplete
+            || (self.pending_len() > 0 && journal.appended() < JOURNAL_COMPACTION_THRESHOLD)
+        {
+            return;
+        }
+        let mut records = Vec::new();
+        records.extend(
+            self.metrics
+                .iter()
+                .map(|(seq, m)| (*seq, Record::Metric(m.clone()))),
+        );
+        records.extend(
+            self.params
+                .iter()
+                .map(|(seq, p)| (*seq, Record::Param(p.clone()))),
+        );
+        records.extend(
+            self.tags
+                .iter()
+                .map(|(seq, t)| (*seq, Record::Tag(t.clone()))),
+        );
+        if self.status != RunStatus::Running {
+            let end = Record::End {
+                status: self.status,
+                end_time: self.end_time,
+            };
+            records.push((0, end));
+        }
+        let r = self.journal.as_mut().unwrap().rewrite(records);
+        self.push_error(r.err());
+    }
     fn push_error(&mut self, e: Option<Error>) {
         if self.error.is_none() {
             self.error = e;
@@ -42,49 +477,261 @@ impl Data {
     }
 }
 
+struct Shared {
+    run: MlflowRun,
+    options: MlflowRunWriterOptions,
+    data: Mutex<Data>,
+    changed: Condvar,
+}
+impl Shared {
+    /// Ends the run with `status` unless it has already been ended, and wakes the worker to send the remaining logs.
+    fn begin_end(&self, status: RunStatus) {
+        let mut d = self.data.lock().unwrap();
+        if d.status != RunStatus::Running {
+            return;
+        }
+        if !d.stopped {
+            let metrics = d.take_aggregated(&self.options.aggregations);
+            if let Err(e) = d.enqueue_metrics(metrics) {
+                d.push_error(Some(e));
+            }
+        }
+        d.status = status;
+        d.end_time = Some(Timestamp::now());
+        let end_time = d.end_time;
+        if let Err(e) = d.append_journal([Record::End { status, end_time }]) {
+            d.push_error(Some(e));
+        }
+        self.changed.notify_all();
+    }
+    /// Makes room for `n` metrics in the queue according to [`MlflowRunWriterOptions::queue_full_policy`].
+    ///
+    /// Errors that occur while waiting are left in `d` to be returned with the result of the call.
+    fn reserve_metrics<'a>(
+        &self,
+        mut d: MutexGuard<'a, Data>,
+        n: usize,
+    ) -> Result<MutexGuard<'a, Data>> {
+        let Some(max) = self.options.max_queue_size else {
+            return Ok(d);
+        };
+        let is_full = |d: &Data| !d.metrics.is_empty() && d.metrics.len() + n > max;
+        match self.options.queue_full_policy {
+            QueueFullPolicy::Block => {
+                d.blocked += 1;
+                // While the server is unreachable, the queue stays full, and the metrics are kept in the journal anyway.
+                while is_full(&d)
+                    && d.status == RunStatus::Running
+                    && d.error.is_none()
+                    && d.outage_error.is_none()
+                {
+                    self.changed.notify_all();
+                    d = self.changed.wait(d).unwrap();
+                }
+                d.blocked -= 1;
+                if d.status != RunStatus::Running {
+                    return Err(ended_error());
+                }
+            }
+            QueueFullPolicy::DropOldest => {
+                let excess = (d.metrics.len() + n)
+                    .saturating_sub(max)
+                    .min(d.metrics.len());
+                let dropped = d
+                    .metrics
+                    .drain(..excess)
+                    .map(|(seq, _)| seq)
+                    .collect::<Vec<_>>();
+                d.acknowledge(dropped);
+                d.done.metrics += excess as u64;
+                d.stats.dropped += excess as u64;
+            }
+            QueueFullPolicy::Error => {
+                if is_full(&d) {
+                    return Err(Error::from_message("The metrics queue is full"));
+                }
+            }
+        }
+        Ok(d)
+    }
+}
+
 /// Writer for outputting logs to [Run](https://mlflow.org/docs/latest/tracking.html#runs).
 ///
 /// This differs from [`MlflowRun`] in the following ways:
 /// - Methods other than [`finish`](Self::finish) may output logs asynchronously for performance reasons.
 ///   When output is asynchronous, any errors that occur will be returned in subsequent method calls.
 /// - If an instance is dropped without calling [`finish`](Self::finish), the Run's status will be set to Failed.
+///   If it is dropped during a panic and [`MlflowRunWriterOptions::capture_panic_message`] is `true`,
+///   the panic message is also recorded in the [`PANIC_MESSAGE_TAG`] tag.
+/// - If `MlflowRunWriterOptions::handle_signals` is `true`, the Run's status will be set to Killed on SIGINT, SIGTERM or SIGHUP.
 /// - Log timestamps will be set to the time when the method is called
 ///
+/// To log from multiple threads, use [`logger`](Self::logger) to obtain a cloneable [`MlflowRunLogger`].
+///
+/// Batching and the queue of pending logs can be configured with [`MlflowRunWriterOptions`].
+///
 /// To obtain a `MlflowRunWriter`, use [`MlflowExperiment::start_run`] or [`MlflowExperiment::start_run_with`].
 ///
 /// [`MlflowExperiment::start_run`]: crate::MlflowExperiment::start_run
 /// [`MlflowExperiment::start_run_with`]: crate::MlflowExperiment::start_run_with
 pub struct MlflowRunWriter {
-    run: MlflowRun,
+    logger: MlflowRunLogger,
     is_end: bool,
-    data: Arc<Mutex<Data>>,
 }
 
 impl MlflowRunWriter {
-    pub(crate) fn new(run: MlflowRun) -> Self {
-        Self {
+    pub(crate) fn new(run: MlflowRun, options: MlflowRunWriterOptions) -> Result<Self> {
+        let mut data = Data {
+            metrics: Vec::new(),
+            params: Vec::new(),
+            tags: Vec::new(),
+            param_values: HashMap::new(),
+            model_id: None,
+            error: None,
+            status: RunStatus::Running,
+            end_time: None,
+            task: None,
+            sampler: None,
+            capture: None,
+            enqueued: Progress::default(),
+            done: Progress::default(),
+            flush_requests: 0,
+            blocked: 0,
+            stopped: false,
+            stats: MlflowRunWriterStats::default(),
+            last_send: None,
+            journal: None,
+            journal_incomplete: false,
+            retry_at: None,
+            outage_backoff: Duration::ZERO,
+            outage_error: None,
+            exited: false,
+            global_step: 0,
+            next_steps: HashMap::new(),
+            scope_steps: HashMap::new(),
+            aggregators: HashMap::new(),
+        };
+        for m in &run.data().data.metrics {
+            if let Some(step) = m.step {
+                data.next_steps.insert(m.key.clone(), step + 1);
+            }
+        }
+        for p in &run.data().data.params {
+            data.param_values.insert(p.key.clone(), p.value.clone());
+        }
+        if let Some(dir) = &options.journal_dir {
+            let (journal, records) = Journal::open(dir, run.id())?;
+            data.journal = Some(journal);
+            for (seq, r) in records {
+                match r {
+                    Record::Metric(m) => {
+                        data.metrics.push((seq, m));
+                        data.enqueued.metrics += 1;
+                    }
+                    Record::Param(p) => {
+                        if !data.param_values.contains_key(&p.key) {
+                            data.param_values.insert(p.key.clone(), p.value.clone());
+                            data.params.push((seq, p));
+                            data.enqueued.params += 1;
+                        }
+                    }
+                    Record::Tag(t) => data.set_tag_with_seq(seq, t),
+                    Record::End { .. } | Record::Ack { .. } => {}
+                }
+            }
+        }
+        let shared = Arc::new(Shared {
             run,
+            options,
+            changed: Condvar::new(),
+            data: Mutex::new(data),
+        });
+        let task = spawn({
+            let shared = shared.clone();
+            move || run_task(&shared)
+        });
+        shared.data.lock().unwrap().task = Some(task);
+        let writer = Self {
+            logger: MlflowRunLogger { shared },
             is_end: false,
-            data: Arc::new(Mutex::new(Data {
-                metrics: Vec::new(),
-                error: None,
-                status: RunStatus::Running,
-                end_time: None,
-                task: None,
-            })),
+        };
+        if let Some(interval) = writer.logger.shared.options.system_metrics_interval {
+            let sampler = system_metrics::spawn_sampler(writer.logger.shared.clone(), interval);
+            writer.logger.shared.data.lock().unwrap().sampler = Some(sampler);
         }
+        if let Some(capture) = &writer.logger.shared.options.output_capture {
+            let capture = output::spawn_capture(writer.logger.shared.clone(), capture)?;
+            writer.logger.shared.data.lock().unwrap().capture = Some(capture);
+        }
+        if writer.logger.shared.options.capture_panic_message {
+            termination::install_panic_hook();
+        }
+        #[cfg(feature = "signals")]
+        if writer.logger.shared.options.handle_signals {
+            termination::register_signal_handler(&writer.logger.shared)?;
+        }
+        Ok(writer)
+    }
+    /// Creates a Run nested under this Run in the same experiment and returns its writer.
+    ///
+    /// The child Run has the [`Run::PARENT_RUN_ID_TAG`](crate::data::Run::PARENT_RUN_ID_TAG) tag
+    /// and uses the same [`MlflowRunWriterOptions`] as this writer.
+    pub fn start_child_run(&self, name: &str) -> Result<MlflowRunWriter> {
+        let shared = &self.logger.shared;
+        shared.run.start_child_run(name, shared.options.clone())
+    }
+
+    /// Returns the last step logged for the metric `key` before this writer was created.
+    ///
+    /// Use this to continue logging from the last step after [`Mlflow::resume_run`](crate::Mlflow::resume_run).
+    pub fn last_step(&self, key: &str) -> Option<i64> {
+        let metrics = &self.run().data().data.metrics;
+        metrics.iter().find(|m| m.key == key).and_then(|m| m.step)
     }
     pub fn run(&self) -> &MlflowRun {
-        &self.run
+        self.logger.run()
+    }
+
+    /// Returns a handle for logging to this run from other threads.
+    ///
+    /// Logs written through the handle are also waited for by [`finish`](Self::finish).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+    /// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+    /// let run = experiment.start_run("run_name")?;
+    /// std::thread::scope(|s| {
+    ///     for rank in 0..4 {
+    ///         let logger = run.logger();
+    ///         s.spawn(move || logger.log_metric(&format!("loss_{rank}"), 0.0, Some(0)));
+    ///     }
+    /// });
+    /// run.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn logger(&self) -> MlflowRunLogger {
+        self.logger.clone()
     }
 
     /// Logs a single parameter.
+    ///
+    /// Parameters are sent asynchronously together with metrics and tags.
+    /// Logging a parameter that was already logged with a different value returns an error immediately,
+    /// and logging it again with the same value is ignored.
     pub fn log_param(&mut self, key: &str, value: &str) -> Result<()> {
-        self.run.log_param(key, value)
+        self.logger.log_param(key, value)
     }
 
     /// Logs multiple parameters.
     ///
+    /// Nested fields of `values` are flattened into keys separated by `.`.
+    /// See [`log_param`](Self::log_param) for how parameters are sent.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -107,42 +754,246 @@ impl MlflowRunWriter {
     /// # }
     /// ```
     pub fn log_params(&mut self, key: &str, values: impl Serialize) -> Result<()> {
-        self.run.log_params(key, values)
+        self.logger.log_params(key, values)
     }
-    pub fn log_metric(&mut self, key: &str, value: f64, step: Option<i64>) -> Result<()> {
-        let mut d = self.data.lock().unwrap();
-        d.metrics.push(Metric {
-            key: key.to_string(),
-            value,
-            timestamp: Timestamp::now(),
-            step,
-        });
-        self.spawn_task(&mut d);
-        d.take_error()?;
+
+    /// Sets a tag on the run.
+    ///
+    /// Tags are sent asynchronously together with metrics and parameters.
+    pub fn set_tag(&mut self, key: &str, value: &str) -> Result<()> {
+        self.logger.set_tag(key, value)
+    }
+
+    /// Uploads `data` as an artifact at `path` relative to the artifact root of the run.
+    ///
+    /// Unlike metrics, artifacts are uploaded synchronously.
+    pub fn log_artifact(&mut self, path: &str, data: Vec<u8>) -> Result<()> {
+        self.run().log_artifact(path, data)
+    }
+
+    /// Uploads `value` as a JSON or YAML artifact.
+    ///
+    /// See [`MlflowRun::log_dict`] for details.
+    pub fn log_dict(&mut self, value: &impl Serialize, path: &str) -> Result<()> {
+        self.run().log_dict(value, path)
+    }
+
+    /// Uploads a table as a JSON artifact, appending to the existing table at `path`.
+    ///
+    /// See [`MlflowRun::log_table`] for details.
+    pub fn log_table(
+        &mut self,
+        columns: &[&str],
+        rows: &[Vec<serde_json::Value>],
+        path: &str,
+    ) -> Result<()> {
+        self.run().log_table(columns, rows, path)
+    }
+
+    /// Uploads an image for `key` at `step` so that it appears in the image gallery of the MLflow UI.
+    ///
+    /// See [`MlflowRun::log_image`] for details.
+    #[cfg(feature = "image")]
+    pub fn log_image(&mut self, image: crate::Image, key: &str, step: Option<i64>) -> Result<()> {
+        self.run().log_image(image, key, step)
+    }
+
+    /// Uploads the files in `dir` as the checkpoint of `step` and returns it.
+    ///
+    /// Pending logs are sent first, and the latest values of the metrics of the run are recorded in the checkpoint.
+    /// Then the checkpoints not kept by [`checkpoint_policy`](MlflowRunWriterOptions::checkpoint_policy) are deleted.
+    /// A checkpoint of the same step is replaced.
+    ///
+    /// Use [`MlflowRun::latest_checkpoint`] or [`MlflowRun::best_checkpoint`] to download a checkpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use mlflow_client::{CheckpointPolicy, KeepBest, MetricGoal, MlflowRunWriterOptions};
+    ///
+    /// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+    /// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+    /// let options = MlflowRunWriterOptions {
+    ///     checkpoint_policy: CheckpointPolicy {
+    ///         keep_last: Some(2),
+    ///         keep_best: Some(KeepBest {
+    ///             metric: "val_loss".to_string(),
+    ///             goal: MetricGoal::Minimize,
+    ///             count: 1,
+    ///         }),
+    ///     },
+    ///     ..Default::default()
+    /// };
+    /// let mut run = experiment.start_run_with_writer_options("run_name", Default::default(), options)?;
+    /// for epoch in 0..10 {
+    ///     // train and write the model to `checkpoint/`
+    ///     run.log_metric("val_loss", 1.0 / (epoch + 1) as f64, Some(epoch))?;
+    ///     run.save_checkpoint(epoch, "checkpoint")?;
+    /// }
+    /// run.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_checkpoint(&mut self, step: i64, dir: impl AsRef<Path>) -> Result<Checkpoint> {
+        self.flush()?;
+        let metrics = self
+            .run()
+            .reload()?
+            .data()
+            .data
+            .metrics
+            .iter()
+            .map(|m| (m.key.clone(), m.value))
+            .collect();
+        let policy = &self.logger.shared.options.checkpoint_policy;
+        checkpoint::save(self.run(), step, dir.as_ref(), metrics, policy)
+    }
+
+    /// Records the build provenance of a crate as tags and artifacts.
+    ///
+    /// Use [`build_info!`](crate::build_info!) to obtain the information of the calling crate.
+    /// See [`build_info`](mod@crate::build_info) for the recorded tags and artifacts.
+    pub fn log_build_info(&mut self, info: &BuildInfo) -> Result<()> {
+        let (tags, artifacts) = info.collect();
+        for (path, data) in artifacts {
+            self.log_artifact(path, data)?;
+        }
+        for tag in tags {
+            self.set_tag(&tag.key, &tag.value)?;
+        }
         Ok(())
     }
+
+    /// Sets the [LoggedModel](crate::MlflowLoggedModel) associated with metrics logged after this call.
+    ///
+    /// Specify `None` to stop associating metrics with a LoggedModel.
+    /// This also applies to metrics logged through [`MlflowRunLogger`].
+    pub fn set_active_model(&mut self, model_id: Option<&str>) {
+        self.logger.shared.data.lock().unwrap().model_id = model_id.map(|id| id.to_string());
+    }
+    pub fn log_metric(&mut self, key: &str, value: f64, step: Option<i64>) -> Result<()> {
+        self.logger.log_metric(key, value, step)
+    }
     pub fn log_metrics(
         &mut self,
         metrics: &[(impl AsRef<str>, f64)],
         step: Option<i64>,
     ) -> Result<()> {
-        let timestamp = Timestamp::now();
-        let mut d = self.data.lock().unwrap();
-        for (key, value) in metrics {
-            d.metrics.push(Metric {
-                key: key.as_ref().to_string(),
-                value: *value,
-                timestamp,
-                step,
-            });
-        }
-        self.spawn_task(&mut d);
-        d.take_error()?;
-        Ok(())
+        self.logger.log_metrics(metrics, step)
+    }
+
+    /// Logs a single metric computed on the specified dataset.
+    ///
+    /// The name and digest of `dataset` are recorded with the metric.
+    pub fn log_metric_for_dataset(
+        &mut self,
+        key: &str,
+        value: f64,
+        step: Option<i64>,
+        dataset: &Dataset,
+    ) -> Result<()> {
+        self.logger
+            .log_metric_for_dataset(key, value, step, dataset)
+    }
+
+    /// Logs multiple metrics computed on the specified dataset.
+    ///
+    /// The name and digest of `dataset` are recorded with the metrics.
+    pub fn log_metrics_for_dataset(
+        &mut self,
+        metrics: &[(impl AsRef<str>, f64)],
+        step: Option<i64>,
+        dataset: &Dataset,
+    ) -> Result<()> {
+        self.logger.log_metrics_for_dataset(metrics, step, dataset)
+    }
+
+    /// Waits until all logs written so far, including those written through [`MlflowRunLogger`], are sent.
+    ///
+    /// Returns an error if sending any logs failed.
+    pub fn flush(&mut self) -> Result<()> {
+        self.logger.flush()
+    }
+
+    /// Returns the counts of logs sent, dropped and failed so far.
+    pub fn stats(&self) -> MlflowRunWriterStats {
+        self.logger.stats()
+    }
+
+    /// Returns the global step used for metrics logged without a step when
+    /// [`step_mode`](MlflowRunWriterOptions::step_mode) is [`StepMode::Global`].
+    pub fn step(&self) -> i64 {
+        self.logger.step()
+    }
+
+    /// Sets the global step.
+    pub fn set_step(&mut self, step: i64) {
+        self.logger.set_step(step)
+    }
+
+    /// Increments the global step and returns the new step.
+    pub fn advance_step(&mut self) -> i64 {
+        self.logger.advance_step()
+    }
+
+    /// Starts a scope such as an epoch, which logs the elapsed time and the averages of the metrics reported within it.
+    ///
+    /// See [`MlflowRunScope`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+    /// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+    /// let run = experiment.start_run("run_name")?;
+    /// for _ in 0..10 {
+    ///     let mut epoch = run.scope("epoch");
+    ///     for batch in 0..100 {
+    ///         epoch.report("loss", 1.0 / (batch + 1) as f64);
+    ///     }
+    ///     // Logs `epoch/loss` and `epoch/elapsed_seconds` with steps 0, 1, 2, ...
+    ///     epoch.finish()?;
+    /// }
+    /// run.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scope(&self, name: &str) -> MlflowRunScope<'_> {
+        self.logger.scope(name)
+    }
+
+    /// Starts tracking the progress towards `total` steps.
+    ///
+    /// See [`MlflowRunProgress`] for the logged metrics and tags.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+    /// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+    /// let run = experiment.start_run("run_name")?;
+    /// let mut progress = run.progress(1000);
+    /// for _ in 0..1000 {
+    ///     // train one step
+    ///     progress.inc(1)?;
+    /// }
+    /// progress.finish()?;
+    /// run.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn progress(&self, total: u64) -> MlflowRunProgress {
+        self.logger.progress(total)
     }
 
     /// Finish the run with the status [`Finished`](RunStatus::Finished).
     ///
+    /// Pending logs, including those written through [`MlflowRunLogger`], are sent before the run is ended.
+    /// After this call, logging through `MlflowRunLogger` returns an error.
+    ///
     /// If this method is not called and the `MlflowRunWriter` is dropped, the status will be [`Failed`](RunStatus::Failed).
     #[doc(alias = "end_run")]
     pub fn finish(mut self) -> Result<()> {
@@ -150,49 +1001,345 @@ impl MlflowRunWriter {
     }
     fn end(&mut self, status: RunStatus) -> Result<()> {
         self.is_end = true;
-
-        let mut d = self.data.lock().unwrap();
-        d.status = status;
-        d.end_time = Some(Timestamp::now());
-        self.spawn_task(&mut d);
-        let task = d.task.take();
-        drop(d);
+        self.logger.shared.begin_end(status);
+        let sampler = self.logger.shared.data.lock().unwrap().sampler.take();
+        if let Some(sampler) = sampler {
+            let _ = sampler.join();
+        }
+        let capture = self.logger.shared.data.lock().unwrap().capture.take();
+        if let Some(capture) = capture {
+            let _ = capture.join();
+        }
+        let task = self.logger.shared.data.lock().unwrap().task.take();
         if let Some(task) = task {
             if task.join().is_err() {
                 return Err(Error::TaskJoinError);
             }
         }
-        let mut d = self.data.lock().unwrap();
+        let mut d = self.logger.shared.data.lock().unwrap();
         d.take_error()?;
         Ok(())
     }
+}
+
+/// Cloneable handle for logging to the run of a [`MlflowRunWriter`] from multiple threads.
+///
+/// To obtain a `MlflowRunLogger`, use [`MlflowRunWriter::logger`].
+/// Metrics are sent asynchronously in the same way as [`MlflowRunWriter`].
+/// Logging after the run is ended returns an error.
+#[derive(Clone)]
+pub struct MlflowRunLogger {
+    shared: Arc<Shared>,
+}
+
+impl MlflowRunLogger {
+    pub fn run(&self) -> &MlflowRun {
+        &self.shared.run
+    }
+
+    /// Logs a single parameter.
+    ///
+    /// See [`MlflowRunWriter::log_param`] for details.
+    pub fn log_param(&self, key: &str, value: &str) -> Result<()> {
+        self.push_params(vec![Param {
+            key: key.to_string(),
+            value: value.to_string(),
+        }])
+    }
+
+    /// Logs multiple parameters.
+    ///
+    /// See [`MlflowRunWriter::log_params`] for details.
+    pub fn log_params(&self, key: &str, values: impl Serialize) -> Result<()> {
+        let values = serde_json::to_value(values)?;
+        let mut params = Vec::new();
+        build_params(key, &values, &mut params)?;
+        self.push_params(params)
+    }
+
+    /// Sets a tag on the run.
+    pub fn set_tag(&self, key: &str, value: &str) -> Result<()> {
+        let mut d = self.lock_running()?;
+        d.set_tag(RunTag {
+            key: key.to_string(),
+            value: value.to_string(),
+        })?;
+        self.shared.changed.notify_all();
+        d.take_error()
+    }
+    pub fn log_metric(&self, key: &str, value: f64, step: Option<i64>) -> Result<()> {
+        self.push_metrics(&[(key, value)], step, None)
+    }
+    pub fn log_metrics(&self, metrics: &[(impl AsRef<str>, f64)], step: Option<i64>) -> Result<()> {
+        self.push_metrics(metrics, step, None)
+    }
+
+    /// Logs a single metric computed on the specified dataset.
+    pub fn log_metric_for_dataset(
+        &self,
+        key: &str,
+        value: f64,
+        step: Option<i64>,
+        dataset: &Dataset,
+    ) -> Result<()> {
+        self.push_metrics(&[(key, value)], step, Some(dataset))
+    }
+
+    /// Logs multiple metrics computed on the specified dataset.
+    pub fn log_metrics_for_dataset(
+        &self,
+        metrics: &[(impl AsRef<str>, f64)],
+        step: Option<i64>,
+        dataset: &Dataset,
+    ) -> Result<()> {
+        self.push_metrics(metrics, step, Some(dataset))
+    }
+
+    /// Waits until all logs written so far are sent.
+    ///
+    /// See [`MlflowRunWriter::flush`] for details.
+    pub fn flush(&self) -> Result<()> {
+        let mut d = self.shared.data.lock().unwrap();
+        let target = d.enqueued;
+        d.flush_requests += 1;
+        self.shared.changed.notify_all();
+        while !d.done.covers(&target) && d.outage_error.is_none() {
+            d = self.shared.changed.wait(d).unwrap();
+        }
+        d.flush_requests -= 1;
+        if let Some(e) = &d.outage_error {
+            return Err(Error::from_message(format!(
+                "Logs are kept in the journal because the server is unreachable: {e}"
+            )));
+        }
+        d.take_error()
+    }
+
+    /// Returns the counts of logs sent, dropped and failed so far.
+    pub fn stats(&self) -> MlflowRunWriterStats {
+        self.shared.data.lock().unwrap().stats
+    }
+
+    /// Returns the global step.
+    ///
+    /// See [`MlflowRunWriter::step`] for details.
+    pub fn step(&self) -> i64 {
+        self.shared.data.lock().unwrap().global_step
+    }
+
+    /// Sets the global step.
+    pub fn set_step(&self, step: i64) {
+        self.shared.data.lock().unwrap().global_step = step;
+    }
+
+    /// Increments the global step and returns the new step.
+    pub fn advance_step(&self) -> i64 {
+        let mut d = self.shared.data.lock().unwrap();
+        d.global_step += 1;
+        d.global_step
+    }
+
+    /// Starts a scope such as an epoch.
+    ///
+    /// See [`MlflowRunWriter::scope`] for details.
+    pub fn scope(&self, name: &str) -> MlflowRunScope<'_> {
+        MlflowRunScope::new(self, name)
+    }
 
-    fn spawn_task(&self, d: &mut Data) {
-        if d.task.is_none() {
-            let run = self.run.clone();
-            let data = self.data.clone();
-            d.task = Some(spawn(move || run_task(run, data)));
+    /// Starts tracking the progress towards `total` steps.
+    ///
+    /// See [`MlflowRunWriter::progress`] for details.
+    pub fn progress(&self, total: u64) -> MlflowRunProgress {
+        MlflowRunProgress::new(self.clone(), total)
+    }
+
+    fn lock_running(&self) -> Result<MutexGuard<'_, Data>> {
+        let mut d = self.shared.data.lock().unwrap();
+        if d.status != RunStatus::Running {
+            return Err(ended_error());
+        }
+        if d.stopped {
+            d.take_error()?;
+            return Err(Error::from_message(
+                "The writer stopped sending logs because of a previous error",
+            ));
         }
+        Ok(d)
     }
+    fn push_params(&self, params: Vec<Param>) -> Result<()> {
+        let mut d = self.lock_running()?;
+        let mut new_values = HashMap::new();
+        for p in &params {
+            let old = d
+                .param_values
+                .get(&p.key)
+                .or_else(|| new_values.get(&p.key).copied());
+            match old {
+                Some(old) if old != &p.value => {
+                    return Err(Error::from_message(format!(
+                        "Parameter `{}` was already logged with a different value (old: `{}`, new: `{}`)",
+                        p.key, old, p.value
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    new_values.insert(p.key.clone(), &p.value);
+                }
+            }
+        }
+        let params = params
+            .into_iter()
+            .filter(|p| !d.param_values.contains_key(&p.key))
+            .collect::<Vec<_>>();
+        let seqs = d.append_journal(params.iter().cloned().map(Record::Param))?;
+        for (seq, p) in seqs.into_iter().zip(params) {
+            if !d.param_values.contains_key(&p.key) {
+                d.param_values.insert(p.key.clone(), p.value.clone());
+                d.params.push((seq, p));
+                d.enqueued.params += 1;
+            }
+        }
+        self.shared.changed.notify_all();
+        d.take_error()
+    }
+    fn push_metrics(
+        &self,
+        metrics: &[(impl AsRef<str>, f64)],
+        step: Option<i64>,
+        dataset: Option<&Dataset>,
+    ) -> Result<()> {
+        let timestamp = Timestamp::now();
+        let d = self.lock_running()?;
+        let mut d = self.shared.reserve_metrics(d, metrics.len())?;
+        let step_mode = self.shared.options.step_mode;
+        let metrics = metrics
+            .iter()
+            .map(|(key, value)| {
+                let key = key.as_ref();
+                let step = step.or(match step_mode {
+                    StepMode::None => None,
+                    StepMode::PerKey => Some(d.next_steps.get(key).copied().unwrap_or(0)),
+                    StepMode::Global => Some(d.global_step),
+                });
+                if let Some(step) = step {
+                    let next = d.next_steps.entry(key.to_string()).or_insert(0);
+                    *next = (*next).max(step + 1);
+                }
+                Metric {
+                    key: key.to_string(),
+                    value: *value,
+                    timestamp,
+                    step,
+                    model_id: d.model_id.clone(),
+                    dataset_name: dataset.map(|d| d.name.clone()),
+                    dataset_digest: dataset.map(|d| d.digest.clone()),
+                }
+            })
+            .collect::<Vec<_>>();
+        let metrics = d.aggregate(&self.shared.options.aggregations, metrics);
+        d.enqueue_metrics(metrics)?;
+        self.shared.changed.notify_all();
+        d.take_error()?;
+        Ok(())
+    }
+}
+
+fn ended_error() -> Error {
+    Error::from_message("The run has already ended")
 }
+
 impl Drop for MlflowRunWriter {
     fn drop(&mut self) {
         if !self.is_end {
+            if thread::panicking() && self.logger.shared.options.capture_panic_message {
+                if let Some(message) = termination::panic_message() {
+                    let _ = self.logger.set_tag(PANIC_MESSAGE_TAG, &message);
+                }
+            }
             let _ = self.end(RunStatus::Failed);
         }
     }
 }
 
-fn run_task(run: MlflowRun, data: Arc<Mutex<Data>>) {
-    let mut err = None;
+/// Background worker that sends logs until the run is ended.
+fn run_task(shared: &Shared) {
+    let run = &shared.run;
+    let mut next_heartbeat = Instant::now();
+    let mut d = shared.data.lock().unwrap();
     loop {
-        let mut d = data.lock().unwrap();
-        if d.error.is_none() && !d.metrics.is_empty() {
-            let metrics = take(&mut d.metrics);
+        if let Some(interval) = shared.options.heartbeat_interval {
+            if d.status == RunStatus::Running && !d.stopped && Instant::now() >= next_heartbeat {
+                next_heartbeat = Instant::now() + interval;
+                drop(d);
+                // Sent apart from the logs, so that it is neither journaled nor counted in the stats.
+                // A failed heartbeat is not an error of the logs, and is tried again at the next interval.
+                let _ = run.set_tag(HEARTBEAT_TAG, &Timestamp::now().0.to_string());
+                d = shared.data.lock().unwrap();
+                continue;
+            }
+        }
+        if d.pending_len() > 0 {
+            if let Some(wait) = d.coalesce_wait(&shared.options) {
+                d = shared.changed.wait_timeout(d, wait).unwrap().0;
+                continue;
+            }
+            let (metrics, params, tags) = d.take_batch(shared.options.max_batch_size);
+            shared.changed.notify_all();
             drop(d);
-            if let Err(e) = run.log_batch(&metrics, &[], &[]) {
-                err = err.or(Some(e));
+            let (metric_seqs, metrics): (Vec<_>, Vec<_>) = metrics.into_iter().unzip();
+            let (param_seqs, params): (Vec<_>, Vec<_>) = params.into_iter().unzip();
+            let (tag_seqs, tags): (Vec<_>, Vec<_>) = tags.into_iter().unzip();
+            let r = with_retry(&shared.options, || run.log_batch(&metrics, &params, &tags));
+            let len = (metrics.len() + params.len() + tags.len()) as u64;
+            d = shared.data.lock().unwrap();
+            d.last_send = Some(Instant::now());
+            match r {
+                Err(e) if d.journal.is_some() && is_transient(&e) => {
+                    if d.status == RunStatus::Running {
+                        // The server is unreachable. Keep the logs and try again later.
+                        prepend(&mut d.metrics, metric_seqs.into_iter().zip(metrics));
+                        prepend(&mut d.params, param_seqs.into_iter().zip(params));
+                        prepend(&mut d.tags, tag_seqs.into_iter().zip(tags));
+                        d.outage_backoff = (d.outage_backoff * 2)
+                            .clamp(shared.options.retry_interval, MAX_OUTAGE_BACKOFF);
+                        d.retry_at = Some(Instant::now() + d.outage_backoff);
+                        d.outage_error = Some(e.to_string());
+                    } else {
+                        // Give up sending and leave the logs in the journal for a later replay.
+                        d.done.metrics += metrics.len() as u64;
+                        d.done.params += params.len() as u64;
+                        d.done.tags += tags.len() as u64;
+                        d.stats.failed += len + d.clear_pending().len() as u64;
+                        d.journal_incomplete = true;
+                        d.push_error(Some(e));
+                    }
+                }
+                r => {
+                    d.done.metrics += metrics.len() as u64;
+                    d.done.params += params.len() as u64;
+                    d.done.tags += tags.len() as u64;
+                    d.retry_at = None;
+                    d.outage_backoff = Duration::ZERO;
+                    d.outage_error = None;
+                    // Sent or failed with an error that retrying does not resolve.
+                    d.acknowledge(metric_seqs.into_iter().chain(param_seqs).chain(tag_seqs));
+                    match r {
+                        Ok(()) => d.stats.sent += len,
+                        Err(e) => {
+                            d.stats.failed += len;
+                            d.push_error(Some(e));
+                            if shared.options.error_policy == ErrorPolicy::FailFast {
+                                d.stopped = true;
+                                let dropped = d.clear_pending();
+                                d.stats.dropped += dropped.len() as u64;
+                                d.acknowledge(dropped);
+                            }
+                        }
+                    }
+                    d.compact_journal();
+                }
             }
+            shared.changed.notify_all();
             continue;
         }
         if d.status != RunStatus::Running {
@@ -202,13 +1349,118 @@ fn run_task(run: MlflowRun, data: Arc<Mutex<Data>>) {
                 ..Default::default()
             };
             drop(d);
-            if let Err(e) = run.update(options) {
-                err = err.or(Some(e));
+            let r = with_retry(&shared.options, || run.update(options));
+            d = shared.data.lock().unwrap();
+            if let Some(journal) = d.journal.take() {
+                if r.is_ok() && !d.journal_incomplete {
+                    d.push_error(journal.remove().err());
+                }
             }
-            d = data.lock().unwrap();
+            d.push_error(r.err());
+            d.exited = true;
+            shared.changed.notify_all();
+            break;
         }
-        d.push_error(err);
-        d.task.take();
-        break;
+        d = if shared.options.heartbeat_interval.is_some() {
+            let wait = next_heartbeat.saturating_duration_since(Instant::now());
+            shared.changed.wait_timeout(d, wait).unwrap().0
+        } else {
+            shared.changed.wait(d).unwrap()
+        };
+    }
+}
+
+fn with_retry<T>(options: &MlflowRunWriterOptions, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut interval = options.retry_interval;
+    let mut retries = 0;
+    loop {
+        match f() {
+            Err(e) if retries < options.max_retries && is_transient(&e) => {
+                sleep(interval);
+                interval *= 2;
+                retries += 1;
+            }
+            r => return r,
+        }
+    }
+}
+
+/// Returns whether a request that failed with `e` may succeed when retried.
+pub(crate) fn is_transient(e: &Error) -> bool {
+    match e {
+        Error::ApiError { error_code, .. } => matches!(
+            error_code.as_str(),
+            "INTERNAL_ERROR" | "TEMPORARILY_UNAVAILABLE" | "REQUEST_LIMIT_EXCEEDED"
+        ),
+        Error::ReqwestError(e) => !e.is_builder(),
+        // Proxies and load balancers may return error pages that are not JSON.
+        Error::JsonError(_) => true,
+        _ => false,
+    }
+}
+
+pub(crate) fn replay_journals(client: &MlflowClient, dir: &Path) -> Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "jsonl") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    let mut run_ids = Vec::new();
+    for path in paths {
+        let Some(run_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let run = MlflowRun::new(client, client.get_run(run_id)?.run);
+        let mut metrics = Vec::new();
+        let mut params = Vec::new();
+        let mut tags = Vec::new();
+        let mut end = None;
+        for r in journal::read(&path)? {
+            match r {
+                Record::Metric(m) => metrics.push(m),
+                Record::Param(p) => {
+                    if !params.iter().any(|x: &Param| x.key == p.key) {
+                        params.push(p);
+                    }
+                }
+                Record::Tag(t) => {
+                    tags.retain(|x: &RunTag| x.key != t.key);
+                    tags.push(t);
+                }
+                Record::End { status, end_time } => end = Some((status, end_time)),
+                Record::Ack { .. } => {}
+            }
+        }
+        run.log_batch(&metrics, &params, &tags)?;
+        if let Some((status, end_time)) = end {
+            run.update(UpdateRunOptions {
+                status: Some(status),
+                end_time,
+                ..Default::default()
+            })?;
+        }
+        fs::remove_file(&path)?;
+        run_ids.push(run_id.to_string());
+    }
+    Ok(run_ids)
+}
+
+fn prepend<T>(items: &mut Vec<T>, front: impl IntoIterator<Item = T>) {
+    items.splice(0..0, front);
+}
+
+fn take_front<T>(items: &mut Vec<T>, n: usize) -> Vec<T> {
+    if items.len() <= n {
+        take(items)
+    } else {
+        items.drain(..n).collect()
     }
 }
@@ -0,0 +1,149 @@
+use std::time::{Duration, Instant};
+
+use crate::data::Metric;
+
+/// Reduction applied to the values of a metric within an [`AggregationWindow`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reduction {
+    Mean,
+    Min,
+    Max,
+    /// The last value in the window.
+    Last,
+    /// Exponential moving average with the specified smoothing factor in `(0, 1]`.
+    ///
+    /// Unlike other reductions, the average carries over to subsequent windows.
+    /// When multiple reductions are logged, the suffix includes the smoothing factor, such as `loss/ema0.1`.
+    Ema(f64),
+    /// Percentile in `[0, 100]` by the nearest-rank method.
+    Percentile(f64),
+}
+impl Reduction {
+    /// Returns the suffix of the key used when multiple reductions are logged.
+    fn suffix(&self) -> String {
+        match self {
+            Reduction::Mean => "mean".to_string(),
+            Reduction::Min => "min".to_string(),
+            Reduction::Max => "max".to_string(),
+            Reduction::Last => "last".to_string(),
+            Reduction::Ema(alpha) => format!("ema{alpha}"),
+            Reduction::Percentile(p) => format!("p{p}"),
+        }
+    }
+}
+
+/// Range of values reduced into one logged value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregationWindow {
+    /// The specified number of logged values.
+    Count(usize),
+    /// Values logged within the specified time from the first value of the window.
+    Duration(Duration),
+}
+
+/// Aggregation of a metric logged by [`MlflowRunWriter`](crate::MlflowRunWriter).
+///
+/// Values logged for the metric are not sent individually.
+/// Instead, each reduction of the values in a window is sent when the window is closed,
+/// with the step and timestamp of the last value in the window.
+/// If only one reduction is specified, it is logged with the key of the metric.
+/// Otherwise, each reduction is logged with a suffixed key such as `loss/mean` and `loss/max`.
+///
+/// A window of [`AggregationWindow::Duration`] is closed when a value is logged after the duration has elapsed.
+/// The remaining values are reduced and logged when the run is ended.
+///
+/// Use with [`MlflowRunWriterOptions::aggregations`](crate::MlflowRunWriterOptions::aggregations).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregation {
+    pub reductions: Vec<Reduction>,
+    pub window: AggregationWindow,
+}
+
+/// State of the current window of an [`Aggregation`].
+pub(super) struct Aggregator {
+    values: Vec<f64>,
+    start: Option<Instant>,
+    /// Moving average of each reduction, indexed by its position in [`Aggregation::reductions`].
+    emas: Vec<Option<f64>>,
+    last: Option<Metric>,
+}
+impl Aggregator {
+    pub(super) fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            start: None,
+            emas: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Adds a value and returns the aggregated metrics if the window is closed.
+    pub(super) fn push(&mut self, aggregation: &Aggregation, m: Metric) -> Vec<Metric> {
+        let mut results = Vec::new();
+        if let AggregationWindow::Duration(d) = aggregation.window {
+            if self.start.is_some_and(|start| start.elapsed() >= d) {
+                results = self.take(aggregation);
+            }
+        }
+        self.start.get_or_insert_with(Instant::now);
+        self.emas.resize(aggregation.reductions.len(), None);
+        for (r, ema) in aggregation.reductions.iter().zip(&mut self.emas) {
+            if let Reduction::Ema(alpha) = r {
+                *ema = Some(match *ema {
+                    Some(ema) => alpha * m.value + (1.0 - alpha) * ema,
+                    None => m.value,
+                });
+            }
+        }
+        self.values.push(m.value);
+        self.last = Some(m);
+        if let AggregationWindow::Count(n) = aggregation.window {
+            if self.values.len() >= n {
+                results = self.take(aggregation);
+            }
+        }
+        results
+    }
+
+    /// Closes the current window and returns the aggregated metrics.
+    pub(super) fn take(&mut self, aggregation: &Aggregation) -> Vec<Metric> {
+        let Some(last) = self.last.take() else {
+            return Vec::new();
+        };
+        let values = std::mem::take(&mut self.values);
+        self.start = None;
+        let suffixed = aggregation.reductions.len() > 1;
+        aggregation
+            .reductions
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let value = match r {
+                    Reduction::Mean => values.iter().sum::<f64>() / values.len() as f64,
+                    Reduction::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+                    Reduction::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    Reduction::Last => last.value,
+                    Reduction::Ema(_) => self.emas.get(i).copied().flatten().unwrap_or(last.value),
+                    Reduction::Percentile(p) => percentile(&values, *p),
+                };
+                let key = if suffixed {
+                    format!("{}/{}", last.key, r.suffix())
+                } else {
+                    last.key.clone()
+                };
+                Metric {
+                    key,
+                    value,
+                    ..last.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+fn percentile(values: &[f64], p: f64) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_by(f64::total_cmp);
+    let rank = (p / 100.0 * values.len() as f64).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
@@ -0,0 +1,170 @@
+use std::{
+    collections::HashSet,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{Metric, Param, RunStatus, RunTag, Timestamp},
+    Result,
+};
+
+/// A line of the write-ahead journal.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Record {
+    Metric(Metric),
+    Param(Param),
+    Tag(RunTag),
+    End {
+        status: RunStatus,
+        end_time: Option<Timestamp>,
+    },
+    /// Sequence numbers of the records that no longer need to be sent, as ranges of `[start, end)`.
+    Ack {
+        seqs: Vec<[u64; 2]>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Line {
+    #[serde(default)]
+    seq: u64,
+    #[serde(flatten)]
+    record: Record,
+}
+
+/// Append-only file of logs that have not been acknowledged by the server yet.
+///
+/// Each record has a sequence number, starting from 1.
+/// Acknowledged records are recorded by [`Record::Ack`] and skipped when the journal is read.
+pub(crate) struct Journal {
+    path: PathBuf,
+    file: File,
+    appended: usize,
+    next_seq: u64,
+}
+
+impl Journal {
+    pub fn path(dir: &Path, run_id: &str) -> PathBuf {
+        dir.join(format!("{run_id}.jsonl"))
+    }
+
+    /// Opens the journal of the run and returns the unacknowledged records left by a previous process,
+    /// with their sequence numbers.
+    pub fn open(dir: &Path, run_id: &str) -> Result<(Self, Vec<(u64, Record)>)> {
+        fs::create_dir_all(dir)?;
+        let path = Self::path(dir, run_id);
+        let (records, last_seq) = read_lines(&path)?;
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok((
+            Self {
+                path,
+                file,
+                appended: 0,
+                next_seq: last_seq + 1,
+            },
+            records,
+        ))
+    }
+
+    /// Number of records appended since the journal was last rewritten.
+    pub fn appended(&self) -> usize {
+        self.appended
+    }
+
+    /// Appends `records` and returns their sequence numbers.
+    pub fn append(&mut self, records: impl IntoIterator<Item = Record>) -> Result<Vec<u64>> {
+        let mut buf = Vec::new();
+        let mut seqs = Vec::new();
+        for record in records {
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            serde_json::to_writer(&mut buf, &Line { seq, record })?;
+            buf.push(b'\n');
+            seqs.push(seq);
+        }
+        self.file.write_all(&buf)?;
+        self.appended += seqs.len();
+        Ok(seqs)
+    }
+
+    /// Records that the records with the sequence numbers `seqs` no longer need to be sent.
+    pub fn acknowledge(&mut self, seqs: impl IntoIterator<Item = u64>) -> Result<()> {
+        let mut seqs = seqs.into_iter().collect::<Vec<_>>();
+        if seqs.is_empty() {
+            return Ok(());
+        }
+        seqs.sort_unstable();
+        let mut ranges = Vec::<[u64; 2]>::new();
+        for seq in seqs {
+            match ranges.last_mut() {
+                Some(r) if r[1] == seq => r[1] += 1,
+                _ => ranges.push([seq, seq + 1]),
+            }
+        }
+        self.append([Record::Ack { seqs: ranges }])?;
+        Ok(())
+    }
+
+    /// Replaces the contents of the journal with `records`, keeping their sequence numbers.
+    pub fn rewrite(&mut self, records: impl IntoIterator<Item = (u64, Record)>) -> Result<()> {
+        let mut records = records.into_iter().peekable();
+        if records.peek().is_none() {
+            self.file.set_len(0)?;
+        } else {
+            let tmp = self.path.with_extension("jsonl.tmp");
+            let mut w = BufWriter::new(File::create(&tmp)?);
+            for (seq, record) in records {
+                serde_json::to_writer(&mut w, &Line { seq, record })?;
+                w.write_all(b"\n")?;
+            }
+            w.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            fs::rename(&tmp, &self.path)?;
+            self.file = OpenOptions::new().append(true).open(&self.path)?;
+        }
+        self.appended = 0;
+        Ok(())
+    }
+
+    pub fn remove(self) -> Result<()> {
+        drop(self.file);
+        fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Returns the records of the journal at `path` that have not been acknowledged.
+pub(crate) fn read(path: &Path) -> Result<Vec<Record>> {
+    Ok(read_lines(path)?.0.into_iter().map(|(_, r)| r).collect())
+}
+
+/// Returns the unacknowledged records with their sequence numbers, and the largest sequence number in the journal.
+fn read_lines(path: &Path) -> Result<(Vec<(u64, Record)>, u64)> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e.into()),
+    };
+    let mut records = Vec::new();
+    let mut acked = HashSet::new();
+    let mut last_seq = 0;
+    for line in BufReader::new(file).lines() {
+        // The last line may be incomplete if the process crashed while writing it.
+        let Ok(line) = serde_json::from_str::<Line>(&line?) else {
+            break;
+        };
+        last_seq = last_seq.max(line.seq);
+        match line.record {
+            Record::Ack { seqs } => {
+                acked.extend(seqs.into_iter().flat_map(|[start, end]| start..end))
+            }
+            record => records.push((line.seq, record)),
+        }
+    }
+    records.retain(|(seq, _)| !acked.contains(seq));
+    Ok((records, last_seq))
+}
@@ -0,0 +1,348 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+    mem,
+    path::PathBuf,
+    sync::{Arc, Mutex, Weak},
+    thread::{spawn, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use super::Shared;
+use crate::{data::RunStatus, Error, MlflowRun, Result};
+
+/// Artifact path of the captured output.
+///
+/// When the output exceeds [`OutputCapture::max_file_size`], it is rotated and uploaded as
+/// `logs/output.1.log`, `logs/output.2.log`, ... in order, and the rest of the output is written to this path.
+pub const OUTPUT_ARTIFACT: &str = "logs/output.log";
+
+/// Capture of the console output of the process, uploaded as artifacts of the run.
+///
+/// The output is teed: it is still written to the console, and also written to `output.log` in [`dir`](Self::dir).
+/// The file is uploaded as [`OUTPUT_ARTIFACT`] every [`upload_interval`](Self::upload_interval)
+/// if it has changed, and once more when the run is ended.
+///
+/// Only one run in a process can capture the output at a time.
+///
+/// Use with [`MlflowRunWriterOptions::output_capture`](crate::MlflowRunWriterOptions::output_capture).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputCapture {
+    /// Capture the standard output. Supported only on Unix.
+    pub stdout: bool,
+    /// Capture the standard error. Supported only on Unix.
+    pub stderr: bool,
+    /// Capture the records of the `log` crate at or above this level. `None` disables the capture.
+    ///
+    /// The capture installs a logger with [`log::set_logger`], so it has no effect if another logger is installed.
+    #[cfg(feature = "log")]
+    pub log_level: Option<log::LevelFilter>,
+    /// Directory of the local files. If `None`, a directory in [`env::temp_dir`] is used and removed
+    /// after the output is uploaded when the run is ended.
+    pub dir: Option<PathBuf>,
+    /// Size in bytes at which the local file is rotated.
+    pub max_file_size: u64,
+    pub upload_interval: Duration,
+}
+impl Default for OutputCapture {
+    fn default() -> Self {
+        Self {
+            stdout: true,
+            stderr: true,
+            #[cfg(feature = "log")]
+            log_level: Some(log::LevelFilter::Info),
+            dir: None,
+            max_file_size: 10 * 1024 * 1024,
+            upload_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+static ACTIVE: Mutex<Weak<Output>> = Mutex::new(Weak::new());
+
+/// Starts capturing the output and returns a thread that uploads it until the run is ended.
+pub(super) fn spawn_capture(
+    shared: Arc<Shared>,
+    options: &OutputCapture,
+) -> Result<JoinHandle<()>> {
+    let (dir, remove_dir) = match &options.dir {
+        Some(dir) => (dir.clone(), false),
+        None => (
+            env::temp_dir().join(format!("mlflow-output-{}", shared.run.id())),
+            true,
+        ),
+    };
+    let output = {
+        // Checked before creating the file, so that the file of the capturing run is not truncated.
+        let mut active = ACTIVE.lock().unwrap();
+        if active.strong_count() > 0 {
+            return Err(Error::from_message(
+                "the output is already captured by another run",
+            ));
+        }
+        fs::create_dir_all(&dir)?;
+        let output = Arc::new(Output {
+            file: Mutex::new(OutputFile {
+                file: File::create(dir.join("output.log"))?,
+                len: 0,
+                rotated: 0,
+                uploaded: 0,
+                modified: false,
+            }),
+            dir,
+            max_file_size: options.max_file_size,
+            #[cfg(feature = "log")]
+            log_level: options.log_level,
+        });
+        *active = Arc::downgrade(&output);
+        output
+    };
+    #[cfg(feature = "log")]
+    if let Some(level) = options.log_level {
+        log_capture::install(level);
+    }
+    #[cfg(unix)]
+    let redirects = {
+        let mut redirects = Vec::new();
+        for (enabled, fd) in [(options.stdout, 1), (options.stderr, 2)] {
+            if enabled {
+                match redirect::Redirect::start(fd, output.clone()) {
+                    Ok(r) => redirects.push(r),
+                    Err(e) => {
+                        redirects.into_iter().for_each(redirect::Redirect::stop);
+                        *ACTIVE.lock().unwrap() = Weak::new();
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        redirects
+    };
+    let interval = options.upload_interval;
+    Ok(spawn(move || {
+        let mut next = Instant::now() + interval;
+        loop {
+            let mut d = shared.data.lock().unwrap();
+            while d.status == RunStatus::Running {
+                let Some(wait) = next.checked_duration_since(Instant::now()) else {
+                    break;
+                };
+                d = shared.changed.wait_timeout(d, wait).unwrap().0;
+            }
+            if d.status != RunStatus::Running {
+                break;
+            }
+            drop(d);
+            next += interval;
+            if let Err(e) = output.upload(&shared.run) {
+                shared.data.lock().unwrap().push_error(Some(e));
+            }
+        }
+        #[cfg(unix)]
+        redirects.into_iter().for_each(redirect::Redirect::stop);
+        *ACTIVE.lock().unwrap() = Weak::new();
+        let r = output.upload(&shared.run);
+        if r.is_ok() && remove_dir {
+            let _ = fs::remove_dir_all(&output.dir);
+        }
+        shared.data.lock().unwrap().push_error(r.err());
+    }))
+}
+
+struct Output {
+    dir: PathBuf,
+    max_file_size: u64,
+    #[cfg(feature = "log")]
+    log_level: Option<log::LevelFilter>,
+    file: Mutex<OutputFile>,
+}
+
+struct OutputFile {
+    file: File,
+    len: u64,
+    /// Number of rotated files.
+    rotated: u64,
+    /// Number of rotated files that have been uploaded and removed.
+    uploaded: u64,
+    /// Whether the current file has changed since it was last uploaded.
+    modified: bool,
+}
+
+impl Output {
+    fn write(&self, buf: &[u8]) {
+        let mut f = self.file.lock().unwrap();
+        if f.file.write_all(buf).is_err() {
+            return;
+        }
+        f.len += buf.len() as u64;
+        f.modified = true;
+        if f.len >= self.max_file_size {
+            let n = f.rotated + 1;
+            let current = self.dir.join("output.log");
+            if fs::rename(&current, self.dir.join(format!("output.{n}.log"))).is_err() {
+                return;
+            }
+            if let Ok(file) = File::create(&current) {
+                f.file = file;
+                f.len = 0;
+                f.rotated = n;
+            }
+        }
+    }
+
+    /// Uploads the rotated files that have not been uploaded, and the current file if it has changed.
+    fn upload(&self, run: &MlflowRun) -> Result<()> {
+        let (uploaded, rotated) = {
+            let f = self.file.lock().unwrap();
+            (f.uploaded, f.rotated)
+        };
+        for n in uploaded + 1..=rotated {
+            let path = self.dir.join(format!("output.{n}.log"));
+            run.log_artifact(&format!("logs/output.{n}.log"), fs::read(&path)?)?;
+            fs::remove_file(path)?;
+            self.file.lock().unwrap().uploaded = n;
+        }
+        let data = {
+            let mut f = self.file.lock().unwrap();
+            if !mem::replace(&mut f.modified, false) {
+                return Ok(());
+            }
+            fs::read(self.dir.join("output.log"))?
+        };
+        let r = run.log_artifact(OUTPUT_ARTIFACT, data);
+        if r.is_err() {
+            self.file.lock().unwrap().modified = true;
+        }
+        r
+    }
+}
+
+#[cfg(unix)]
+mod redirect {
+    use std::{
+        fs::File,
+        io::{self, Read, Write},
+        os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+        sync::Arc,
+        thread::{spawn, JoinHandle},
+    };
+
+    use super::Output;
+
+    /// Redirection of a file descriptor to a pipe read by a thread that tees the output.
+    pub(super) struct Redirect {
+        fd: RawFd,
+        saved: OwnedFd,
+        reader: JoinHandle<()>,
+    }
+    impl Redirect {
+        pub(super) fn start(fd: RawFd, output: Arc<Output>) -> io::Result<Self> {
+            let mut fds = [0; 2];
+            // SAFETY: `fds` is valid for writes of two file descriptors.
+            if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: The file descriptors were just created and are owned by nobody else.
+            let (read, write) =
+                unsafe { (File::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+            // SAFETY: `fd` is a file descriptor of the process. The duplicate is owned by `saved`.
+            let saved = unsafe { libc::dup(fd) };
+            if saved < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: `saved` was just created by `dup`.
+            let saved = unsafe { OwnedFd::from_raw_fd(saved) };
+            let mut console = File::from(saved.try_clone()?);
+            flush(fd);
+            // SAFETY: Both are valid file descriptors.
+            if unsafe { libc::dup2(write.as_raw_fd(), fd) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            drop(write);
+            let reader = spawn(move || {
+                let mut read = read;
+                let mut buf = [0; 8192];
+                loop {
+                    match read.read(&mut buf) {
+                        Ok(0) => break,
+                        Ok(n) => {
+                            let _ = console.write_all(&buf[..n]);
+                            output.write(&buf[..n]);
+                        }
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                        Err(_) => break,
+                    }
+                }
+            });
+            Ok(Self { fd, saved, reader })
+        }
+
+        /// Restores the file descriptor and waits until the remaining output is teed.
+        pub(super) fn stop(self) {
+            flush(self.fd);
+            // SAFETY: Both are valid file descriptors.
+            unsafe { libc::dup2(self.saved.as_raw_fd(), self.fd) };
+            let _ = self.reader.join();
+        }
+    }
+
+    fn flush(fd: RawFd) {
+        match fd {
+            1 => _ = io::stdout().flush(),
+            2 => _ = io::stderr().flush(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+mod log_capture {
+    use std::sync::OnceLock;
+
+    use log::{LevelFilter, Log, Metadata, Record};
+
+    use super::ACTIVE;
+
+    struct CaptureLogger;
+
+    impl Log for CaptureLogger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            ACTIVE
+                .lock()
+                .unwrap()
+                .upgrade()
+                .and_then(|output| output.log_level)
+                .is_some_and(|level| metadata.level() <= level)
+        }
+
+        fn log(&self, record: &Record) {
+            let Some(output) = ACTIVE.lock().unwrap().upgrade() else {
+                return;
+            };
+            if output
+                .log_level
+                .is_some_and(|level| record.level() <= level)
+            {
+                let line = format!(
+                    "[{} {}] {}\n",
+                    record.level(),
+                    record.target(),
+                    record.args()
+                );
+                output.write(line.as_bytes());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Installs the logger that writes records to the active capture, if no other logger is installed.
+    pub(super) fn install(level: LevelFilter) {
+        static INSTALLED: OnceLock<bool> = OnceLock::new();
+        static LOGGER: CaptureLogger = CaptureLogger;
+        if *INSTALLED.get_or_init(|| log::set_logger(&LOGGER).is_ok()) {
+            log::set_max_level(level);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::{Duration, Instant};
+
+use super::{MlflowRunLogger, MlflowRunWriter};
+use crate::Result;
+
+/// Tag key for the human-readable progress of a run, such as `42/100 (42.0%), ETA 1m 05s`.
+pub const PROGRESS_TAG: &str = "mlflow_client.progress";
+
+/// Tracks the progress of a run towards a total number of steps.
+///
+/// The following are logged with the number of completed steps as the step,
+/// at most once per [`MlflowRunWriterOptions::progress_interval`](crate::MlflowRunWriterOptions::progress_interval)
+/// and when the progress ends:
+///
+/// - `progress` metric: Fraction of completed steps in `[0, 1]`.
+/// - `eta_seconds` metric: Estimated time to complete the remaining steps, once at least one step is completed.
+/// - [`PROGRESS_TAG`] tag: The above in a human-readable form.
+///
+/// The progress ends on [`finish`](Self::finish) or when it is dropped.
+///
+/// To obtain a `MlflowRunProgress`, use [`MlflowRunWriter::progress`] or [`MlflowRunLogger::progress`].
+/// To track the items of an iterator, use [`TrackProgress::track`].
+pub struct MlflowRunProgress {
+    logger: MlflowRunLogger,
+    total: u64,
+    completed: u64,
+    start: Instant,
+    interval: Duration,
+    last_log: Option<(Instant, u64)>,
+    is_end: bool,
+}
+
+impl MlflowRunProgress {
+    pub(super) fn new(logger: MlflowRunLogger, total: u64) -> Self {
+        let interval = logger.shared.options.progress_interval;
+        Self {
+            logger,
+            total,
+            completed: 0,
+            start: Instant::now(),
+            interval,
+            last_log: None,
+            is_end: false,
+        }
+    }
+
+    /// Returns the number of completed steps.
+    pub fn completed(&self) -> u64 {
+        self.completed
+    }
+
+    /// Adds `n` to the number of completed steps.
+    pub fn inc(&mut self, n: u64) -> Result<()> {
+        self.set(self.completed + n)
+    }
+
+    /// Sets the number of completed steps.
+    pub fn set(&mut self, completed: u64) -> Result<()> {
+        self.completed = completed;
+        match self.last_log {
+            Some((at, _)) if at.elapsed() < self.interval && completed < self.total => Ok(()),
+            _ => self.log(),
+        }
+    }
+
+    /// Ends the progress and logs the final state.
+    pub fn finish(mut self) -> Result<()> {
+        self.end()
+    }
+
+    fn end(&mut self) -> Result<()> {
+        self.is_end = true;
+        if self
+            .last_log
+            .is_some_and(|(_, completed)| completed == self.completed)
+        {
+            return Ok(());
+        }
+        self.log()
+    }
+
+    fn log(&mut self) -> Result<()> {
+        self.last_log = Some((Instant::now(), self.completed));
+        let progress = if self.total == 0 {
+            1.0
+        } else {
+            (self.completed as f64 / self.total as f64).min(1.0)
+        };
+        let mut status = format!(
+            "{}/{} ({:.1}%)",
+            self.completed,
+            self.total,
+            progress * 100.0
+        );
+        let mut metrics = vec![("progress", progress)];
+        if self.completed > 0 {
+            let elapsed = self.start.elapsed().as_secs_f64();
+            let remaining = self.total.saturating_sub(self.completed);
+            let eta = elapsed / self.completed as f64 * remaining as f64;
+            metrics.push(("eta_seconds", eta));
+            if remaining > 0 {
+                status += &format!(", ETA {}", format_seconds(eta));
+            } else {
+                status += &format!(", done in {}", format_seconds(elapsed));
+            }
+        }
+        self.logger
+            .log_metrics(&metrics, Some(self.completed as i64))?;
+        self.logger.set_tag(PROGRESS_TAG, &status)
+    }
+}
+
+impl Drop for MlflowRunProgress {
+    fn drop(&mut self) {
+        if !self.is_end {
+            let _ = self.end();
+        }
+    }
+}
+
+fn format_seconds(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    let (h, m, s) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if h > 0 {
+        format!("{h}h {m:02}m {s:02}s")
+    } else if m > 0 {
+        format!("{m}m {s:02}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+/// Extension of [`Iterator`] to track its progress with [`MlflowRunProgress`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use mlflow_client::TrackProgress;
+///
+/// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+/// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+/// let run = experiment.start_run("run_name")?;
+/// let batches = vec![1.0, 2.0, 3.0];
+/// let total = batches.len() as u64;
+/// for batch in batches.into_iter().track(&run, total) {
+///     // train on the batch
+/// #   let _ = batch;
+/// }
+/// run.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait TrackProgress: Iterator + Sized {
+    /// Counts each item returned by the iterator as a completed step of `total` steps.
+    ///
+    /// An item is counted as completed when the next item is requested.
+    ///
+    /// Errors in logging the progress are ignored.
+    fn track(self, writer: &MlflowRunWriter, total: u64) -> Tracked<Self> {
+        self.track_with(writer.progress(total))
+    }
+
+    /// Counts each item returned by the iterator as a completed step of `progress`.
+    fn track_with(self, progress: MlflowRunProgress) -> Tracked<Self> {
+        Tracked {
+            iter: self,
+            progress: Some(progress),
+            pending: false,
+        }
+    }
+}
+impl<I: Iterator> TrackProgress for I {}
+
+/// Iterator returned by [`TrackProgress::track`].
+///
+/// The progress ends when the iterator is exhausted or dropped.
+pub struct Tracked<I> {
+    iter: I,
+    progress: Option<MlflowRunProgress>,
+    pending: bool,
+}
+
+impl<I: Iterator> Iterator for Tracked<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if let Some(progress) = &mut self.progress {
+            if self.pending {
+                let _ = progress.inc(1);
+            }
+        }
+        let item = self.iter.next();
+        self.pending = item.is_some();
+        if item.is_none() {
+            if let Some(progress) = self.progress.take() {
+                let _ = progress.finish();
+            }
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
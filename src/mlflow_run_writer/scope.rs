@@ -0,0 +1,94 @@
+use std::time::Instant;
+
+use super::MlflowRunLogger;
+use crate::Result;
+
+/// A period of a run, such as an epoch, that logs a summary of the metrics reported within it.
+///
+/// When the scope ends, the following metrics are logged with the number of previously ended scopes
+/// of the same name as the step (0, 1, 2, ...), continuing from the steps already logged to the run:
+///
+/// - `{name}/{key}`: Average of the values reported for `key`.
+/// - `{name}/elapsed_seconds`: Time from the start to the end of the scope.
+///
+/// The scope ends on [`finish`](Self::finish) or when it is dropped.
+///
+/// To obtain a `MlflowRunScope`, use [`MlflowRunWriter::scope`](crate::MlflowRunWriter::scope)
+/// or [`MlflowRunLogger::scope`].
+pub struct MlflowRunScope<'a> {
+    logger: &'a MlflowRunLogger,
+    name: String,
+    start: Instant,
+    sums: Vec<(String, f64, u64)>,
+    is_end: bool,
+}
+
+impl<'a> MlflowRunScope<'a> {
+    pub(super) fn new(logger: &'a MlflowRunLogger, name: &str) -> Self {
+        Self {
+            logger,
+            name: name.to_string(),
+            start: Instant::now(),
+            sums: Vec::new(),
+            is_end: false,
+        }
+    }
+
+    /// Adds a value to the average of `key` logged when the scope ends.
+    pub fn report(&mut self, key: &str, value: f64) {
+        if let Some((_, sum, count)) = self.sums.iter_mut().find(|(k, _, _)| k == key) {
+            *sum += value;
+            *count += 1;
+        } else {
+            self.sums.push((key.to_string(), value, 1));
+        }
+    }
+
+    /// Logs a metric without a step and adds it to the average of `key` logged when the scope ends.
+    ///
+    /// The step of the metric is determined by [`MlflowRunWriterOptions::step_mode`](crate::MlflowRunWriterOptions::step_mode).
+    pub fn log_metric(&mut self, key: &str, value: f64) -> Result<()> {
+        self.logger.log_metric(key, value, None)?;
+        self.report(key, value);
+        Ok(())
+    }
+
+    /// Ends the scope and logs the summary.
+    pub fn finish(mut self) -> Result<()> {
+        self.end()
+    }
+
+    fn end(&mut self) -> Result<()> {
+        self.is_end = true;
+        let step = {
+            let mut d = self.logger.shared.data.lock().unwrap();
+            // Continue from the scopes of the same name logged to the run before it was resumed.
+            let first = d
+                .next_steps
+                .get(&format!("{}/elapsed_seconds", self.name))
+                .copied()
+                .unwrap_or(0);
+            let step = d.scope_steps.entry(self.name.clone()).or_insert(first);
+            *step += 1;
+            *step - 1
+        };
+        let mut metrics = self
+            .sums
+            .iter()
+            .map(|(key, sum, count)| (format!("{}/{key}", self.name), sum / *count as f64))
+            .collect::<Vec<_>>();
+        metrics.push((
+            format!("{}/elapsed_seconds", self.name),
+            self.start.elapsed().as_secs_f64(),
+        ));
+        self.logger.log_metrics(&metrics, Some(step))
+    }
+}
+
+impl Drop for MlflowRunScope<'_> {
+    fn drop(&mut self) {
+        if !self.is_end {
+            let _ = self.end();
+        }
+    }
+}
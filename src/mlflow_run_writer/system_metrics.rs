@@ -0,0 +1,274 @@
+use std::{
+    fs,
+    sync::Arc,
+    thread::{spawn, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use super::Shared;
+use crate::data::{Metric, RunStatus, Timestamp};
+
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Starts a thread that logs system metrics every `interval` until the run is ended.
+///
+/// Metrics are named and numbered in the same way as the system metrics of the MLflow Python client.
+/// Each metric is logged only when its source can be read, so nothing is logged on platforms other than Linux.
+/// Steps continue from the system metrics already logged to the run, and samples are enqueued
+/// according to [`queue_full_policy`](crate::MlflowRunWriterOptions::queue_full_policy) in the same way as other metrics.
+pub(super) fn spawn_sampler(shared: Arc<Shared>, interval: Duration) -> JoinHandle<()> {
+    spawn(move || {
+        let mut sampler = Sampler::new();
+        let mut step = shared
+            .data
+            .lock()
+            .unwrap()
+            .next_steps
+            .iter()
+            .filter(|(key, _)| key.starts_with("system/"))
+            .map(|(_, step)| *step)
+            .max()
+            .unwrap_or(0);
+        let mut next = Instant::now() + interval;
+        loop {
+            let mut d = shared.data.lock().unwrap();
+            while d.status == RunStatus::Running {
+                let Some(wait) = next.checked_duration_since(Instant::now()) else {
+                    break;
+                };
+                d = shared.changed.wait_timeout(d, wait).unwrap().0;
+            }
+            if d.status != RunStatus::Running || d.stopped {
+                return;
+            }
+            drop(d);
+            next += interval;
+
+            let metrics = sampler.sample();
+            let timestamp = Timestamp::now();
+            let d = shared.data.lock().unwrap();
+            let mut d = match shared.reserve_metrics(d, metrics.len()) {
+                Ok(d) if d.status == RunStatus::Running && !d.stopped => d,
+                Ok(_) => return,
+                // The queue is full with `QueueFullPolicy::Error`, so this sample is skipped.
+                Err(_) if shared.data.lock().unwrap().status == RunStatus::Running => {
+                    step += 1;
+                    continue;
+                }
+                Err(_) => return,
+            };
+            let metrics = metrics
+                .into_iter()
+                .map(|(key, value)| Metric {
+                    key: key.to_string(),
+                    value,
+                    timestamp,
+                    step: Some(step),
+                    model_id: None,
+                    dataset_name: None,
+                    dataset_digest: None,
+                })
+                .collect::<Vec<_>>();
+            if let Err(e) = d.enqueue_metrics(metrics) {
+                d.push_error(Some(e));
+                return;
+            }
+            shared.changed.notify_all();
+            step += 1;
+        }
+    })
+}
+
+struct Sampler {
+    cpu: Option<CpuTimes>,
+    network: Option<(u64, u64)>,
+}
+impl Sampler {
+    fn new() -> Self {
+        Self {
+            cpu: CpuTimes::read(),
+            network: read_network_bytes(),
+        }
+    }
+    fn sample(&mut self) -> Vec<(&'static str, f64)> {
+        let mut metrics = Vec::new();
+        let cpu = CpuTimes::read();
+        if let (Some(prev), Some(cpu)) = (&self.cpu, &cpu) {
+            if let Some(value) = cpu.utilization_since(prev) {
+                metrics.push(("system/cpu_utilization_percentage", value));
+            }
+        }
+        self.cpu = cpu;
+        if let Some((used, total)) = read_memory_bytes() {
+            metrics.push(("system/system_memory_usage_megabytes", used as f64 / MB));
+            metrics.push((
+                "system/system_memory_usage_percentage",
+                used as f64 / total as f64 * 100.0,
+            ));
+        }
+        if let Some((used, available)) = read_disk_bytes() {
+            metrics.push(("system/disk_usage_megabytes", used as f64 / MB));
+            metrics.push(("system/disk_available_megabytes", available as f64 / MB));
+            metrics.push((
+                "system/disk_usage_percentage",
+                used as f64 / (used + available) as f64 * 100.0,
+            ));
+        }
+        if let (Some((rx0, tx0)), Some((rx, tx))) = (self.network, read_network_bytes()) {
+            metrics.push((
+                "system/network_receive_megabytes",
+                rx.saturating_sub(rx0) as f64 / MB,
+            ));
+            metrics.push((
+                "system/network_transmit_megabytes",
+                tx.saturating_sub(tx0) as f64 / MB,
+            ));
+        }
+        metrics
+    }
+}
+
+/// CPU time consumed so far, from the cgroup if it has a CPU quota, otherwise from `/proc/stat`.
+enum CpuTimes {
+    Cgroup {
+        at: Instant,
+        usage_usec: u64,
+        cpus: f64,
+    },
+    Host {
+        busy: u64,
+        total: u64,
+    },
+}
+impl CpuTimes {
+    fn read() -> Option<Self> {
+        Self::read_cgroup().or_else(Self::read_host)
+    }
+    fn read_cgroup() -> Option<Self> {
+        let max = fs::read_to_string("/sys/fs/cgroup/cpu.max").ok()?;
+        let (quota, period) = max.trim().split_once(' ')?;
+        let cpus = quota.parse::<f64>().ok()? / period.parse::<f64>().ok()?;
+        let stat = fs::read_to_string("/sys/fs/cgroup/cpu.stat").ok()?;
+        let usage_usec = stat
+            .lines()
+            .find_map(|l| l.strip_prefix("usage_usec "))?
+            .parse()
+            .ok()?;
+        Some(Self::Cgroup {
+            at: Instant::now(),
+            usage_usec,
+            cpus,
+        })
+    }
+    fn read_host() -> Option<Self> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        let times = stat
+            .lines()
+            .next()?
+            .strip_prefix("cpu ")?
+            .split_whitespace()
+            .map(|s| s.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        // user nice system idle iowait irq softirq steal ...
+        let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+        let total = times.iter().take(8).sum::<u64>();
+        Some(Self::Host {
+            busy: total - idle,
+            total,
+        })
+    }
+    fn utilization_since(&self, prev: &Self) -> Option<f64> {
+        let value = match (prev, self) {
+            (
+                Self::Cgroup {
+                    at: at0,
+                    usage_usec: usage0,
+                    ..
+                },
+                Self::Cgroup {
+                    at,
+                    usage_usec,
+                    cpus,
+                },
+            ) => {
+                let elapsed = at.duration_since(*at0).as_secs_f64() * 1_000_000.0 * cpus;
+                usage_usec.saturating_sub(*usage0) as f64 / elapsed
+            }
+            (
+                Self::Host {
+                    busy: busy0,
+                    total: total0,
+                },
+                Self::Host { busy, total },
+            ) => busy.saturating_sub(*busy0) as f64 / total.saturating_sub(*total0) as f64,
+            _ => return None,
+        };
+        value.is_finite().then(|| (value * 100.0).min(100.0))
+    }
+}
+
+/// Returns the used and total memory, limited to the cgroup if it has a memory limit.
+fn read_memory_bytes() -> Option<(u64, u64)> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| -> Option<u64> {
+        let line = meminfo.lines().find_map(|l| l.strip_prefix(name))?;
+        let kb = line.trim_start_matches(':').split_whitespace().next()?;
+        Some(kb.parse::<u64>().ok()? * 1024)
+    };
+    let host_total = field("MemTotal")?;
+    let host_used = host_total.saturating_sub(field("MemAvailable")?);
+    let cgroup = [
+        ("/sys/fs/cgroup/memory.current", "/sys/fs/cgroup/memory.max"),
+        (
+            "/sys/fs/cgroup/memory/memory.usage_in_bytes",
+            "/sys/fs/cgroup/memory/memory.limit_in_bytes",
+        ),
+    ]
+    .into_iter()
+    .find_map(|(current, max)| {
+        let read = |path| fs::read_to_string(path).ok()?.trim().parse::<u64>().ok();
+        Some((read(current)?, read(max)?))
+    });
+    match cgroup {
+        Some((used, limit)) if limit < host_total => Some((used, limit)),
+        _ => Some((host_used, host_total)),
+    }
+}
+
+/// Returns the used and available space of the root file system.
+#[cfg(target_os = "linux")]
+fn read_disk_bytes() -> Option<(u64, u64)> {
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: The path is a valid NUL-terminated string and `stat` is valid for writes.
+    let stat = unsafe {
+        if libc::statvfs(c"/".as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stat.assume_init()
+    };
+    let block = stat.f_frsize as u64;
+    let used = (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * block;
+    let available = stat.f_bavail as u64 * block;
+    Some((used, available))
+}
+#[cfg(not(target_os = "linux"))]
+fn read_disk_bytes() -> Option<(u64, u64)> {
+    None
+}
+
+/// Returns the bytes received and transmitted by all interfaces other than loopback.
+fn read_network_bytes() -> Option<(u64, u64)> {
+    let dev = fs::read_to_string("/proc/net/dev").ok()?;
+    let mut rx = 0;
+    let mut tx = 0;
+    for line in dev.lines().skip(2) {
+        let (name, values) = line.split_once(':')?;
+        if name.trim() == "lo" {
+            continue;
+        }
+        let values = values.split_whitespace().collect::<Vec<_>>();
+        rx += values.first()?.parse::<u64>().ok()?;
+        tx += values.get(8)?.parse::<u64>().ok()?;
+    }
+    Some((rx, tx))
+}
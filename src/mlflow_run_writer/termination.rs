@@ -0,0 +1,183 @@
+use std::{cell::RefCell, panic, sync::Once};
+
+#[cfg(feature = "signals")]
+use std::{
+    sync::{Arc, Mutex, OnceLock, Weak},
+    time::Instant,
+};
+
+#[cfg(feature = "signals")]
+use super::Shared;
+#[cfg(feature = "signals")]
+use crate::{data::RunStatus, Error, Result};
+
+thread_local! {
+    static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Installs a panic hook that records the panic message of the current thread,
+/// then calls the previously installed hook.
+pub(super) fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let prev = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let payload = info.payload();
+            let message = if let Some(s) = payload.downcast_ref::<&str>() {
+                s.to_string()
+            } else if let Some(s) = payload.downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "Box<dyn Any>".to_string()
+            };
+            let message = match info.location() {
+                Some(l) => format!("{message} (at {l})"),
+                None => message,
+            };
+            let _ = PANIC_MESSAGE.try_with(|m| *m.borrow_mut() = Some(message));
+            prev(info);
+        }));
+    });
+}
+
+/// Returns the message of the last panic on the current thread.
+pub(super) fn panic_message() -> Option<String> {
+    PANIC_MESSAGE
+        .try_with(|m| m.borrow().clone())
+        .ok()
+        .flatten()
+}
+
+#[cfg(feature = "signals")]
+static SIGNAL_TARGETS: Mutex<Vec<Weak<Shared>>> = Mutex::new(Vec::new());
+
+/// Registers a writer to be ended with [`RunStatus::Killed`] on SIGINT, SIGTERM or SIGHUP.
+#[cfg(feature = "signals")]
+pub(super) fn register_signal_handler(shared: &Arc<Shared>) -> Result<()> {
+    static INSTALL: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+    INSTALL
+        .get_or_init(|| signal::install(on_signal))
+        .clone()
+        .map_err(|e| Error::from_message(format!("Failed to install signal handler: {e}")))?;
+    let mut targets = SIGNAL_TARGETS.lock().unwrap();
+    targets.retain(|t| t.strong_count() > 0);
+    targets.push(Arc::downgrade(shared));
+    Ok(())
+}
+
+/// Ends the runs of the registered writers, waits for them to send pending logs, and terminates the process.
+#[cfg(feature = "signals")]
+fn on_signal(signal: i32) {
+    let targets: Vec<_> = SIGNAL_TARGETS
+        .lock()
+        .unwrap()
+        .drain(..)
+        .filter_map(|t| t.upgrade())
+        .collect();
+    for shared in &targets {
+        shared.begin_end(RunStatus::Killed);
+    }
+    for shared in &targets {
+        let deadline = Instant::now() + shared.options.signal_flush_timeout;
+        let mut d = shared.data.lock().unwrap();
+        while !d.exited {
+            let Some(timeout) = deadline.checked_duration_since(Instant::now()) else {
+                break;
+            };
+            d = shared.changed.wait_timeout(d, timeout).unwrap().0;
+        }
+    }
+    signal::terminate(signal);
+}
+
+#[cfg(all(feature = "signals", unix))]
+mod signal {
+    use std::{
+        fs::File,
+        io::{self, Read},
+        mem,
+        os::fd::FromRawFd,
+        process::exit,
+        ptr,
+        sync::atomic::{AtomicI32, Ordering},
+        thread::spawn,
+    };
+
+    use libc::c_int;
+
+    /// Write end of the pipe through which the signal handler passes signal numbers to the handling thread.
+    static PIPE: AtomicI32 = AtomicI32::new(-1);
+
+    extern "C" fn handle(signal: c_int) {
+        let byte = signal as u8;
+        // SAFETY: `write` is async-signal-safe, and `byte` is valid for reads of 1 byte.
+        unsafe { libc::write(PIPE.load(Ordering::Relaxed), ptr::from_ref(&byte).cast(), 1) };
+    }
+
+    /// Installs a handler for SIGINT, SIGTERM and SIGHUP that calls `f` with the signal number on another thread.
+    pub(super) fn install(f: fn(i32)) -> Result<(), String> {
+        let mut fds = [0; 2];
+        // SAFETY: `fds` is valid for writes of two file descriptors.
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error().to_string());
+        }
+        for fd in fds {
+            // SAFETY: `fd` was just created by `pipe`.
+            unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+        }
+        PIPE.store(fds[1], Ordering::Relaxed);
+        // SAFETY: The read end was just created and is owned by nobody else.
+        let mut read = unsafe { File::from_raw_fd(fds[0]) };
+        spawn(move || {
+            let mut buf = [0; 1];
+            loop {
+                match read.read(&mut buf) {
+                    Ok(1) => f(buf[0].into()),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    _ => break,
+                }
+            }
+        });
+        for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+            // SAFETY: `action` is initialized before it is passed to `sigaction`, and `handle` is async-signal-safe.
+            unsafe {
+                let mut action: libc::sigaction = mem::zeroed();
+                action.sa_sigaction = handle as extern "C" fn(c_int) as libc::sighandler_t;
+                action.sa_flags = libc::SA_RESTART;
+                libc::sigemptyset(&mut action.sa_mask);
+                if libc::sigaction(signal, &action, ptr::null_mut()) != 0 {
+                    return Err(io::Error::last_os_error().to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Terminates the process by `signal` with its default action,
+    /// so that the parent process observes that the process was killed by the signal.
+    pub(super) fn terminate(signal: i32) -> ! {
+        // SAFETY: Restoring the default action and raising the signal have no memory safety requirements.
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+        exit(128 + signal)
+    }
+}
+
+#[cfg(all(feature = "signals", not(unix)))]
+mod signal {
+    use std::process::exit;
+
+    /// Exit code used after the runs are ended by Ctrl-C. (128 + SIGINT)
+    const EXIT_CODE: i32 = 130;
+
+    /// Installs a handler for Ctrl-C and the termination events of the console.
+    pub(super) fn install(f: fn(i32)) -> Result<(), String> {
+        ctrlc::set_handler(move || f(0)).map_err(|e| e.to_string())
+    }
+
+    pub(super) fn terminate(_signal: i32) -> ! {
+        exit(EXIT_CODE)
+    }
+}
@@ -0,0 +1,441 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    thread::panicking,
+};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    client::MlflowClient,
+    data::{
+        EndTraceOptions, Span, SpanContext, SpanEvent, SpanStatusCode, SpanType, StartTraceOptions,
+        Timestamp, TraceData, TraceInfo, TraceRequestMetadata, TraceStatus, TraceTag,
+    },
+    utils::{now_nanos, random_hex},
+    Error, Result,
+};
+
+thread_local! {
+    static ACTIVE_SPANS: RefCell<Vec<ActiveSpan>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Maximum length of the trace inputs and outputs stored in the request metadata of [`TraceInfo`].
+const MAX_CHARS_IN_TRACE_METADATA: usize = 250;
+
+/// Creates [traces](https://mlflow.org/docs/latest/llms/tracing/index.html) in an experiment.
+///
+/// To obtain a `MlflowTracer`, use [`MlflowExperiment::tracer`](crate::MlflowExperiment::tracer).
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+/// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+/// let tracer = experiment.tracer();
+///
+/// let root = tracer.span("answer");
+/// root.set_inputs("What is MLflow?")?;
+/// {
+///     let _s = tracer.span("retrieve");
+///     // The span ends when `_s` is dropped.
+/// }
+/// root.set_outputs("MLflow is ...")?;
+/// root.end()?; // Ending the root span sends the trace to the server.
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MlflowTracer {
+    client: MlflowClient,
+    experiment_id: String,
+}
+
+impl MlflowTracer {
+    pub(crate) fn new(client: &MlflowClient, experiment_id: &str) -> MlflowTracer {
+        MlflowTracer {
+            client: client.clone(),
+            experiment_id: experiment_id.to_string(),
+        }
+    }
+
+    /// Starts a span with the type [`Unknown`](SpanType::Unknown).
+    ///
+    /// See [`span_with_type`](Self::span_with_type) for details.
+    pub fn span(&self, name: &str) -> MlflowSpan {
+        self.span_with_type(name, SpanType::Unknown)
+    }
+
+    /// Starts a span.
+    ///
+    /// If the current thread has an active span, the new span becomes its child.
+    /// Otherwise, a new trace is started and the new span becomes its root.
+    /// To create a child span on another thread, use [`MlflowSpan::child`].
+    ///
+    /// The span ends when [`MlflowSpan::end`] is called or when it is dropped,
+    /// and the trace is sent to the server when all of its spans have ended.
+    /// Errors that occur while starting the trace are returned from [`MlflowSpan::end`].
+    pub fn span_with_type(&self, name: &str, span_type: SpanType) -> MlflowSpan {
+        let parent = ACTIVE_SPANS.with(|s| {
+            let mut s = s.borrow_mut();
+            // Spans created on this thread but ended on another thread remain in the list.
+            while let Some(span) = s.last() {
+                if span.is_end() {
+                    s.pop();
+                } else {
+                    break;
+                }
+            }
+            s.last()
+                .map(|span| (span.trace.clone(), span.span_id.clone()))
+        });
+        if let Some((trace, parent_id)) = parent {
+            MlflowSpan::start(trace, Some(parent_id), name, span_type)
+        } else {
+            let trace = Arc::new(Trace::start(self, name));
+            MlflowSpan::start(trace, None, name, span_type)
+        }
+    }
+}
+
+struct ActiveSpan {
+    trace: Arc<Trace>,
+    index: usize,
+    span_id: String,
+}
+impl ActiveSpan {
+    fn is_end(&self) -> bool {
+        self.trace.state.lock().unwrap().spans[self.index]
+            .end_time
+            .is_some()
+    }
+}
+
+struct Trace {
+    client: MlflowClient,
+    state: Mutex<TraceState>,
+}
+
+struct TraceState {
+    trace_id: String,
+    info: Option<TraceInfo>,
+    error: Option<Error>,
+    spans: Vec<Span>,
+    open: usize,
+}
+
+impl Trace {
+    fn start(tracer: &MlflowTracer, name: &str) -> Self {
+        let tags = [TraceTag {
+            key: "mlflow.traceName".to_string(),
+            value: name.to_string(),
+        }];
+        let options = StartTraceOptions {
+            tags: &tags,
+            ..Default::default()
+        };
+        let (info, error) =
+            match tracer
+                .client
+                .start_trace(&tracer.experiment_id, Timestamp::now(), options)
+            {
+                Ok(r) => (Some(r.trace_info), None),
+                Err(e) => (None, Some(e)),
+            };
+        Trace {
+            client: tracer.client.clone(),
+            state: Mutex::new(TraceState {
+                trace_id: format!("0x{}", random_hex(16)),
+                info,
+                error,
+                spans: Vec::new(),
+                open: 0,
+            }),
+        }
+    }
+
+    fn export(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(e) = state.error.take() {
+            return Err(e);
+        }
+        let Some(info) = state.info.take() else {
+            return Ok(());
+        };
+        let spans = state.spans.clone();
+        drop(state);
+
+        let export = TraceExport::new(&info, spans)?;
+        let ended = export.end(&self.client, &info)?;
+        export.upload(&self.client, &info, &ended)
+    }
+}
+
+/// Ending of a trace and uploading of its spans, shared with the OpenTelemetry exporter.
+pub(crate) struct TraceExport {
+    end_time: Timestamp,
+    status: TraceStatus,
+    request_metadata: Vec<TraceRequestMetadata>,
+    data: TraceData,
+}
+
+impl TraceExport {
+    /// Records the request ID of `info` in `spans`,
+    /// and takes the end time, status, inputs and outputs of the trace from the root span.
+    pub(crate) fn new(info: &TraceInfo, mut spans: Vec<Span>) -> Result<Self> {
+        let request_id = serde_json::to_string(&info.request_id)?;
+        for span in &mut spans {
+            span.attributes
+                .insert(Span::REQUEST_ID_ATTRIBUTE.to_string(), request_id.clone());
+        }
+        let mut request_metadata = Vec::new();
+        let mut end_time = Timestamp::now();
+        let mut status = TraceStatus::Ok;
+        if let Some(root) = spans.iter().find(|s| s.parent_id.is_none()) {
+            for (key, attribute) in [
+                ("mlflow.traceInputs", Span::INPUTS_ATTRIBUTE),
+                ("mlflow.traceOutputs", Span::OUTPUTS_ATTRIBUTE),
+            ] {
+                if let Some(value) = root.attributes.get(attribute) {
+                    request_metadata.push(TraceRequestMetadata {
+                        key: key.to_string(),
+                        value: truncate(value, MAX_CHARS_IN_TRACE_METADATA),
+                    });
+                }
+            }
+            if let Some(t) = root.end_time {
+                end_time = Timestamp(t / 1_000_000);
+            }
+            if root.status_code == SpanStatusCode::Error {
+                status = TraceStatus::Error;
+            }
+        }
+        Ok(Self {
+            end_time,
+            status,
+            request_metadata,
+            data: TraceData { spans },
+        })
+    }
+
+    /// Ends the trace started as `info` and returns the updated [`TraceInfo`].
+    pub(crate) fn end(&self, client: &MlflowClient, info: &TraceInfo) -> Result<TraceInfo> {
+        let options = EndTraceOptions {
+            request_metadata: &self.request_metadata,
+            ..Default::default()
+        };
+        let r = client.end_trace(&info.request_id, self.end_time, self.status, options)?;
+        Ok(r.trace_info)
+    }
+
+    /// Uploads the spans to the artifact location of the trace.
+    ///
+    /// The location is taken from `ended` if the server returned it when ending the trace, and from `started` otherwise.
+    pub(crate) fn upload(
+        &self,
+        client: &MlflowClient,
+        started: &TraceInfo,
+        ended: &TraceInfo,
+    ) -> Result<()> {
+        let info = if ended.tag(TraceInfo::ARTIFACT_LOCATION_TAG).is_some() {
+            ended
+        } else {
+            started
+        };
+        client.upload_trace_data(info, &self.data)
+    }
+}
+
+/// A span of a trace created by [`MlflowTracer`].
+///
+/// The span ends when [`end`](Self::end) is called or when it is dropped.
+/// If the span is dropped during a panic, its status is set to [`Error`](SpanStatusCode::Error).
+pub struct MlflowSpan {
+    trace: Arc<Trace>,
+    index: usize,
+    span_id: String,
+    is_end: bool,
+}
+
+impl MlflowSpan {
+    fn start(
+        trace: Arc<Trace>,
+        parent_id: Option<String>,
+        name: &str,
+        span_type: SpanType,
+    ) -> Self {
+        let span_id = format!("0x{}", random_hex(8));
+        let mut state = trace.state.lock().unwrap();
+        let mut attributes = BTreeMap::new();
+        attributes.insert(
+            Span::SPAN_TYPE_ATTRIBUTE.to_string(),
+            serde_json::to_string(&span_type).unwrap(),
+        );
+        let index = state.spans.len();
+        let trace_id = state.trace_id.clone();
+        state.spans.push(Span {
+            name: name.to_string(),
+            context: SpanContext {
+                span_id: span_id.clone(),
+                trace_id,
+            },
+            parent_id,
+            start_time: now_nanos(),
+            end_time: None,
+            status_code: SpanStatusCode::Unset,
+            status_message: String::new(),
+            attributes,
+            events: Vec::new(),
+        });
+        state.open += 1;
+        drop(state);
+        ACTIVE_SPANS.with(|s| {
+            s.borrow_mut().push(ActiveSpan {
+                trace: trace.clone(),
+                index,
+                span_id: span_id.clone(),
+            })
+        });
+        MlflowSpan {
+            trace,
+            index,
+            span_id,
+            is_end: false,
+        }
+    }
+
+    pub fn span_id(&self) -> &str {
+        &self.span_id
+    }
+
+    /// Returns the request ID of the trace, or `None` if the trace could not be started.
+    pub fn request_id(&self) -> Option<String> {
+        let state = self.trace.state.lock().unwrap();
+        state.info.as_ref().map(|info| info.request_id.clone())
+    }
+
+    /// Starts a child span with the type [`Unknown`](SpanType::Unknown).
+    pub fn child(&self, name: &str) -> MlflowSpan {
+        self.child_with_type(name, SpanType::Unknown)
+    }
+
+    /// Starts a child span.
+    ///
+    /// Unlike [`MlflowTracer::span`], this method can be used from any thread.
+    pub fn child_with_type(&self, name: &str, span_type: SpanType) -> MlflowSpan {
+        MlflowSpan::start(
+            self.trace.clone(),
+            Some(self.span_id.clone()),
+            name,
+            span_type,
+        )
+    }
+
+    /// Sets the inputs of this span.
+    pub fn set_inputs(&self, inputs: impl Serialize) -> Result<()> {
+        self.set_attribute(Span::INPUTS_ATTRIBUTE, inputs)
+    }
+
+    /// Sets the outputs of this span.
+    pub fn set_outputs(&self, outputs: impl Serialize) -> Result<()> {
+        self.set_attribute(Span::OUTPUTS_ATTRIBUTE, outputs)
+    }
+
+    /// Sets an attribute of this span. The value is stored as JSON.
+    pub fn set_attribute(&self, key: &str, value: impl Serialize) -> Result<()> {
+        let value = serde_json::to_string(&value)?;
+        self.with_span(|span| {
+            span.attributes.insert(key.to_string(), value);
+        });
+        Ok(())
+    }
+
+    /// Sets the status of this span.
+    ///
+    /// If the status is not set, it will be [`Ok`](SpanStatusCode::Ok) when the span ends.
+    pub fn set_status(&self, status_code: SpanStatusCode, message: &str) {
+        self.with_span(|span| {
+            span.status_code = status_code;
+            span.status_message = message.to_string();
+        });
+    }
+
+    /// Records an event that occurred during this span.
+    ///
+    /// `attributes` must serialize to a JSON object or `null`.
+    pub fn add_event(&self, name: &str, attributes: impl Serialize) -> Result<()> {
+        let attributes = match serde_json::to_value(attributes)? {
+            Value::Null => BTreeMap::new(),
+            Value::Object(m) => m.into_iter().collect(),
+            _ => return Err(Error::from_message("Event attributes must be an object")),
+        };
+        self.with_span(|span| {
+            span.events.push(SpanEvent {
+                name: name.to_string(),
+                timestamp: now_nanos(),
+                attributes,
+            })
+        });
+        Ok(())
+    }
+
+    /// Ends this span.
+    ///
+    /// If this is the last span of the trace to end, the trace is sent to the server
+    /// and any error that occurred while starting or sending the trace is returned.
+    pub fn end(mut self) -> Result<()> {
+        self.end_span()
+    }
+
+    fn end_span(&mut self) -> Result<()> {
+        self.is_end = true;
+        ACTIVE_SPANS.with(|s| {
+            let mut s = s.borrow_mut();
+            if let Some(i) = s
+                .iter()
+                .rposition(|a| Arc::ptr_eq(&a.trace, &self.trace) && a.span_id == self.span_id)
+            {
+                s.remove(i);
+            }
+        });
+        let mut state = self.trace.state.lock().unwrap();
+        let span = &mut state.spans[self.index];
+        span.end_time = Some(now_nanos());
+        if panicking() {
+            span.status_code = SpanStatusCode::Error;
+            span.status_message = "panicked".to_string();
+        } else if span.status_code == SpanStatusCode::Unset {
+            span.status_code = SpanStatusCode::Ok;
+        }
+        state.open -= 1;
+        let is_last = state.open == 0;
+        drop(state);
+        if is_last {
+            self.trace.export()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn with_span<T>(&self, f: impl FnOnce(&mut Span) -> T) -> T {
+        let mut state = self.trace.state.lock().unwrap();
+        f(&mut state.spans[self.index])
+    }
+}
+impl Drop for MlflowSpan {
+    fn drop(&mut self) {
+        if !self.is_end {
+            let _ = self.end_span();
+        }
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((i, _)) => s[..i].to_string(),
+        None => s.to_string(),
+    }
+}
@@ -0,0 +1,436 @@
+//! [OpenTelemetry] exporter that sends spans to MLflow as traces.
+//!
+//! This module is available when the `opentelemetry` feature is enabled.
+//!
+//! [OpenTelemetry]: https://opentelemetry.io/
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Mutex,
+    },
+    thread::{sleep, spawn, JoinHandle},
+    time::{Duration, Instant, SystemTime},
+};
+
+use ::opentelemetry::{
+    trace::{SpanId, Status, TraceId},
+    Array, KeyValue, Value as OtelValue,
+};
+use ::opentelemetry_sdk::{
+    error::{OTelSdkError, OTelSdkResult},
+    trace::{SpanData, SpanExporter},
+};
+use serde_json::{json, Value};
+
+use crate::{
+    client::MlflowClient,
+    data::{
+        Span, SpanContext, SpanEvent, SpanStatusCode, SpanType, StartTraceOptions, Timestamp,
+        TraceTag,
+    },
+    mlflow_run_writer::is_transient,
+    mlflow_tracer::TraceExport,
+    Error, Result,
+};
+
+/// Options for [`MlflowSpanExporter`].
+#[derive(Debug, Clone)]
+pub struct MlflowSpanExporterOptions {
+    /// Maximum number of retries for a failed request.
+    pub max_retries: u32,
+    /// Wait time before the first retry. The wait time doubles with each retry.
+    pub retry_interval: Duration,
+    /// Maximum time to keep spans of a trace whose root span has not been exported yet.
+    pub pending_trace_timeout: Duration,
+}
+impl Default for MlflowSpanExporterOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            retry_interval: Duration::from_millis(500),
+            pending_trace_timeout: Duration::from_secs(600),
+        }
+    }
+}
+
+/// [`SpanExporter`] that converts OpenTelemetry spans into MLflow traces.
+///
+/// Spans are grouped by trace ID and sent as one MLflow trace when the root span of the trace is exported.
+/// Requests are sent by a background thread, and requests that failed with a transient error are retried with exponential backoff.
+/// Spans exported after their root span, such as child spans that end after the root span, are kept until
+/// [`pending_trace_timeout`](MlflowSpanExporterOptions::pending_trace_timeout) and then dropped.
+/// Traces that could not be exported even after retries and expired spans are dropped, and the first error is returned
+/// from the next [`force_flush`](SpanExporter::force_flush) or [`shutdown`](SpanExporter::shutdown).
+///
+/// Span types, inputs and outputs are taken from the `mlflow.spanType`, `mlflow.spanInputs` and `mlflow.spanOutputs` attributes.
+/// If they are absent, they are derived from the [OpenTelemetry GenAI] and [OpenInference] semantic conventions.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use opentelemetry_sdk::trace::SdkTracerProvider;
+///
+/// let mlflow = mlflow_client::Mlflow::new("http://localhost:5000")?;
+/// let experiment = mlflow.create_experiment_if_not_exists("experiment_name", Default::default())?;
+/// let provider = SdkTracerProvider::builder()
+///     .with_batch_exporter(experiment.span_exporter())
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [OpenTelemetry GenAI]: https://opentelemetry.io/docs/specs/semconv/gen-ai/
+/// [OpenInference]: https://github.com/Arize-ai/openinference
+#[derive(Debug)]
+pub struct MlflowSpanExporter {
+    sender: Mutex<Option<Sender<Message>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+enum Message {
+    Spans(Vec<SpanData>),
+    Flush(Sender<Option<Error>>),
+}
+
+impl MlflowSpanExporter {
+    pub fn new(client: MlflowClient, experiment_id: &str) -> Self {
+        Self::with_options(client, experiment_id, MlflowSpanExporterOptions::default())
+    }
+    pub fn with_options(
+        client: MlflowClient,
+        experiment_id: &str,
+        options: MlflowSpanExporterOptions,
+    ) -> Self {
+        let (sender, receiver) = channel();
+        let mut worker = Worker {
+            client,
+            experiment_id: experiment_id.to_string(),
+            options,
+            pending: HashMap::new(),
+            error: None,
+        };
+        Self {
+            sender: Mutex::new(Some(sender)),
+            worker: Some(spawn(move || worker.run(receiver))),
+        }
+    }
+    fn send(&self, message: Message) -> OTelSdkResult {
+        match &*self.sender.lock().unwrap() {
+            Some(sender) => sender
+                .send(message)
+                .map_err(|_| OTelSdkError::InternalFailure("Exporter thread stopped".into())),
+            None => Err(OTelSdkError::AlreadyShutdown),
+        }
+    }
+    /// Waits until the received spans are exported, and returns the first error since the last flush.
+    fn flush(&self) -> OTelSdkResult {
+        let (sender, receiver) = channel();
+        self.send(Message::Flush(sender))?;
+        match receiver.recv() {
+            Ok(None) => Ok(()),
+            Ok(Some(e)) => Err(OTelSdkError::InternalFailure(format!(
+                "Failed to export a trace: {e}"
+            ))),
+            Err(_) => Err(OTelSdkError::InternalFailure(
+                "Exporter thread stopped".into(),
+            )),
+        }
+    }
+}
+
+impl SpanExporter for MlflowSpanExporter {
+    fn export(
+        &self,
+        batch: Vec<SpanData>,
+    ) -> impl std::future::Future<Output = OTelSdkResult> + Send {
+        std::future::ready(self.send(Message::Spans(batch)))
+    }
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.flush()
+    }
+    fn shutdown_with_timeout(&mut self, _timeout: Duration) -> OTelSdkResult {
+        let r = self.flush();
+        self.sender.lock().unwrap().take();
+        if let Some(worker) = self.worker.take() {
+            worker
+                .join()
+                .map_err(|_| OTelSdkError::InternalFailure("Exporter thread panicked".into()))?;
+        }
+        r
+    }
+}
+impl Drop for MlflowSpanExporter {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+struct PendingTrace {
+    spans: Vec<SpanData>,
+    created: Instant,
+}
+
+struct Worker {
+    client: MlflowClient,
+    experiment_id: String,
+    options: MlflowSpanExporterOptions,
+    pending: HashMap<TraceId, PendingTrace>,
+    /// First error in exporting a trace since the last flush.
+    error: Option<Error>,
+}
+
+impl Worker {
+    fn run(&mut self, receiver: Receiver<Message>) {
+        loop {
+            let message = match receiver.recv_timeout(Duration::from_secs(1)) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    self.discard_expired();
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            let mut completed = Vec::new();
+            let mut flushes = Vec::new();
+            let mut next = Some(message);
+            while let Some(message) = next {
+                match message {
+                    Message::Spans(spans) => self.add_spans(spans, &mut completed),
+                    Message::Flush(sender) => flushes.push(sender),
+                }
+                next = receiver.try_recv().ok();
+            }
+            for spans in completed {
+                if let Err(e) = self.export_trace(spans) {
+                    self.error.get_or_insert(e);
+                }
+            }
+            self.discard_expired();
+            for sender in flushes {
+                let _ = sender.send(self.error.take());
+            }
+        }
+    }
+    fn add_spans(&mut self, spans: Vec<SpanData>, completed: &mut Vec<Vec<SpanData>>) {
+        for span in spans {
+            let trace_id = span.span_context.trace_id();
+            let is_root = span.parent_span_id == SpanId::INVALID || span.parent_span_is_remote;
+            let pending = self
+                .pending
+                .entry(trace_id)
+                .or_insert_with(|| PendingTrace {
+                    spans: Vec::new(),
+                    created: Instant::now(),
+                });
+            pending.spans.push(span);
+            if is_root {
+                if let Some(pending) = self.pending.remove(&trace_id) {
+                    completed.push(pending.spans);
+                }
+            }
+        }
+    }
+    /// Discards the traces pending for longer than the timeout, and records an error for the first one.
+    fn discard_expired(&mut self) {
+        let timeout = self.options.pending_trace_timeout;
+        let mut expired = None;
+        self.pending.retain(|trace_id, p| {
+            let keep = p.created.elapsed() < timeout;
+            if !keep {
+                expired.get_or_insert((*trace_id, p.spans.len()));
+            }
+            keep
+        });
+        if let Some((trace_id, len)) = expired {
+            self.error.get_or_insert_with(|| {
+                Error::from_message(format!(
+                    "Dropped {len} spans of trace {trace_id} whose root span was not exported within {timeout:?}"
+                ))
+            });
+        }
+    }
+    fn export_trace(&self, spans: Vec<SpanData>) -> Result<()> {
+        let Some(root) = spans
+            .iter()
+            .find(|s| s.parent_span_id == SpanId::INVALID || s.parent_span_is_remote)
+        else {
+            return Ok(());
+        };
+        let tags = [TraceTag {
+            key: "mlflow.traceName".to_string(),
+            value: root.name.to_string(),
+        }];
+        let start_time = to_timestamp(root.start_time);
+        let info = self.retry(|| {
+            let options = StartTraceOptions {
+                tags: &tags,
+                ..Default::default()
+            };
+            self.client
+                .start_trace(&self.experiment_id, start_time, options)
+        })?;
+        let info = info.trace_info;
+        let export = TraceExport::new(&info, spans.iter().map(convert_span).collect())?;
+        let ended = self.retry(|| export.end(&self.client, &info))?;
+        self.retry(|| export.upload(&self.client, &info, &ended))
+    }
+    fn retry<T>(&self, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut interval = self.options.retry_interval;
+        let mut retries = 0;
+        loop {
+            match f() {
+                Ok(value) => return Ok(value),
+                Err(e) if retries >= self.options.max_retries || !is_transient(&e) => {
+                    return Err(e)
+                }
+                Err(_) => {
+                    sleep(interval);
+                    interval *= 2;
+                    retries += 1;
+                }
+            }
+        }
+    }
+}
+
+fn to_nanos(t: SystemTime) -> i64 {
+    t.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0)
+}
+fn to_timestamp(t: SystemTime) -> Timestamp {
+    Timestamp(to_nanos(t) / 1_000_000)
+}
+
+fn convert_span(span: &SpanData) -> Span {
+    let mut attributes = BTreeMap::new();
+    let mut values = HashMap::new();
+    for KeyValue { key, value, .. } in &span.attributes {
+        values.insert(key.as_str(), value);
+        let value = match value {
+            // Attributes in the `mlflow.` namespace are already encoded as JSON.
+            OtelValue::String(s) if key.as_str().starts_with("mlflow.") => s.as_str().to_string(),
+            value => to_json(value).to_string(),
+        };
+        attributes.insert(key.to_string(), value);
+    }
+    if !attributes.contains_key(Span::SPAN_TYPE_ATTRIBUTE) {
+        let span_type = span_type(&values);
+        attributes.insert(
+            Span::SPAN_TYPE_ATTRIBUTE.to_string(),
+            json!(span_type).to_string(),
+        );
+    }
+    for (attribute, keys) in [
+        (
+            Span::INPUTS_ATTRIBUTE,
+            ["input.value", "gen_ai.input.messages", "gen_ai.prompt"],
+        ),
+        (
+            Span::OUTPUTS_ATTRIBUTE,
+            [
+                "output.value",
+                "gen_ai.output.messages",
+                "gen_ai.completion",
+            ],
+        ),
+    ] {
+        if attributes.contains_key(attribute) {
+            continue;
+        }
+        if let Some(value) = keys.iter().find_map(|key| values.get(key)) {
+            attributes.insert(attribute.to_string(), to_io_json(value).to_string());
+        }
+    }
+    let (status_code, status_message) = match &span.status {
+        Status::Unset => (SpanStatusCode::Unset, String::new()),
+        Status::Ok => (SpanStatusCode::Ok, String::new()),
+        Status::Error { description } => (SpanStatusCode::Error, description.to_string()),
+    };
+    Span {
+        name: span.name.to_string(),
+        context: SpanContext {
+            span_id: format!("0x{}", span.span_context.span_id()),
+            trace_id: format!("0x{}", span.span_context.trace_id()),
+        },
+        parent_id: if span.parent_span_id == SpanId::INVALID || span.parent_span_is_remote {
+            None
+        } else {
+            Some(format!("0x{}", span.parent_span_id))
+        },
+        start_time: to_nanos(span.start_time),
+        end_time: Some(to_nanos(span.end_time)),
+        status_code,
+        status_message,
+        attributes,
+        events: span
+            .events
+            .events
+            .iter()
+            .map(|e| SpanEvent {
+                name: e.name.to_string(),
+                timestamp: to_nanos(e.timestamp),
+                attributes: e
+                    .attributes
+                    .iter()
+                    .map(|kv| (kv.key.to_string(), to_json(&kv.value)))
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn span_type(values: &HashMap<&str, &OtelValue>) -> SpanType {
+    if let Some(OtelValue::String(kind)) = values.get("openinference.span.kind") {
+        return match kind.as_str() {
+            "LLM" => SpanType::Llm,
+            "CHAIN" => SpanType::Chain,
+            "AGENT" => SpanType::Agent,
+            "TOOL" => SpanType::Tool,
+            "RETRIEVER" => SpanType::Retriever,
+            "EMBEDDING" => SpanType::Embedding,
+            "RERANKER" => SpanType::Reranker,
+            _ => SpanType::Unknown,
+        };
+    }
+    if let Some(OtelValue::String(operation)) = values.get("gen_ai.operation.name") {
+        return match operation.as_str() {
+            "chat" => SpanType::ChatModel,
+            "text_completion" | "generate_content" => SpanType::Llm,
+            "embeddings" => SpanType::Embedding,
+            "execute_tool" => SpanType::Tool,
+            "invoke_agent" | "create_agent" => SpanType::Agent,
+            _ => SpanType::Unknown,
+        };
+    }
+    SpanType::Unknown
+}
+
+fn to_json(value: &OtelValue) -> Value {
+    match value {
+        OtelValue::Bool(v) => json!(v),
+        OtelValue::I64(v) => json!(v),
+        OtelValue::F64(v) => json!(v),
+        OtelValue::String(v) => json!(v.as_str()),
+        OtelValue::Array(Array::Bool(v)) => json!(v),
+        OtelValue::Array(Array::I64(v)) => json!(v),
+        OtelValue::Array(Array::F64(v)) => json!(v),
+        OtelValue::Array(Array::String(v)) => {
+            json!(v.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+        }
+        value => json!(value.to_string()),
+    }
+}
+
+/// Converts a value used as span inputs or outputs, which is often a JSON document stored as a string.
+fn to_io_json(value: &OtelValue) -> Value {
+    match value {
+        OtelValue::String(s) => {
+            serde_json::from_str(s.as_str()).unwrap_or_else(|_| json!(s.as_str()))
+        }
+        value => to_json(value),
+    }
+}
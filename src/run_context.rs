@@ -0,0 +1,240 @@
+//! Tags describing the context of the current process, set automatically on new runs.
+//!
+//! Runs created by [`MlflowExperiment::start_run`](crate::MlflowExperiment::start_run) and its variants,
+//! and by [`MlflowRunWriter::start_child_run`](crate::MlflowRunWriter::start_child_run),
+//! get the tags returned by the registered [`RunContextProvider`]s.
+//! Tags specified in [`CreateRunOptions`](crate::data::CreateRunOptions) take precedence over them.
+//!
+//! [`DefaultRunContext`] and [`GitRunContext`] are registered by default.
+//!
+//! # Examples
+//!
+//! ```
+//! use mlflow_client::{data::RunTag, run_context::{self, RunContextProvider}};
+//!
+//! struct ClusterContext;
+//! impl RunContextProvider for ClusterContext {
+//!     fn tags(&self) -> Vec<RunTag> {
+//!         vec![RunTag {
+//!             key: "cluster".to_string(),
+//!             value: "gpu-a".to_string(),
+//!         }]
+//!     }
+//! }
+//! let id = run_context::register(ClusterContext);
+//! // Runs started here get the `cluster` tag.
+//! run_context::unregister(id);
+//! ```
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        RwLock,
+    },
+};
+
+use crate::data::RunTag;
+
+/// Tag that holds the name of the user who created the run.
+pub const USER_TAG: &str = "mlflow.user";
+
+/// Tag that holds the name of the program that created the run.
+pub const SOURCE_NAME_TAG: &str = "mlflow.source.name";
+
+/// Tag that holds the type of the program that created the run.
+pub const SOURCE_TYPE_TAG: &str = "mlflow.source.type";
+
+/// Tag that holds the git commit of the program that created the run.
+pub const GIT_COMMIT_TAG: &str = "mlflow.source.git.commit";
+
+/// Tag that holds the git branch of the program that created the run.
+pub const GIT_BRANCH_TAG: &str = "mlflow.source.git.branch";
+
+/// Tag that holds the URL of the git repository of the program that created the run.
+pub const GIT_REPO_URL_TAG: &str = "mlflow.source.git.repoURL";
+
+/// Source of tags set automatically on new runs.
+pub trait RunContextProvider: Send + Sync {
+    /// Returns the tags to set on a new run.
+    ///
+    /// Tags that cannot be determined should be omitted.
+    fn tags(&self) -> Vec<RunTag>;
+}
+
+/// Provides the [`USER_TAG`], [`SOURCE_NAME_TAG`] and [`SOURCE_TYPE_TAG`] tags.
+///
+/// The user name is read from the `LOGNAME`, `USER` or `USERNAME` environment variable,
+/// and the source name is the path of the current executable.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRunContext;
+
+impl RunContextProvider for DefaultRunContext {
+    fn tags(&self) -> Vec<RunTag> {
+        let mut tags = Vec::new();
+        if let Some(user) = ["LOGNAME", "USER", "USERNAME"]
+            .into_iter()
+            .find_map(|name| env::var(name).ok().filter(|v| !v.is_empty()))
+        {
+            tags.push(tag(USER_TAG, user));
+        }
+        if let Ok(exe) = env::current_exe() {
+            tags.push(tag(SOURCE_NAME_TAG, exe.display().to_string()));
+        }
+        tags.push(tag(SOURCE_TYPE_TAG, "LOCAL".to_string()));
+        tags
+    }
+}
+
+/// Provides the [`GIT_COMMIT_TAG`], [`GIT_BRANCH_TAG`] and [`GIT_REPO_URL_TAG`] tags.
+///
+/// The repository containing the current directory is read directly from its `.git` directory.
+/// The branch is omitted if `HEAD` is detached, and the repository URL is the URL of the `origin` remote.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GitRunContext;
+
+impl GitRunContext {
+    /// Returns the tags of the repository containing `dir`.
+    pub fn tags_in(&self, dir: &Path) -> Vec<RunTag> {
+        let mut tags = Vec::new();
+        let Some(git_dir) = find_git_dir(dir) else {
+            return tags;
+        };
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(s) => git_dir.join(s.trim()),
+            Err(_) => git_dir.clone(),
+        };
+        let Ok(head) = fs::read_to_string(git_dir.join("HEAD")) else {
+            return tags;
+        };
+        let head = head.trim();
+        let commit = if let Some(reference) = head.strip_prefix("ref: ") {
+            if let Some(branch) = reference.strip_prefix("refs/heads/") {
+                tags.push(tag(GIT_BRANCH_TAG, branch.to_string()));
+            }
+            resolve_ref(&common_dir, reference)
+        } else {
+            Some(head.to_string())
+        };
+        if let Some(commit) = commit {
+            tags.push(tag(GIT_COMMIT_TAG, commit));
+        }
+        if let Some(url) = origin_url(&common_dir) {
+            tags.push(tag(GIT_REPO_URL_TAG, url));
+        }
+        tags
+    }
+}
+
+impl RunContextProvider for GitRunContext {
+    fn tags(&self) -> Vec<RunTag> {
+        match env::current_dir() {
+            Ok(dir) => self.tags_in(&dir),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static PROVIDERS: RwLock<Vec<(ProviderId, Box<dyn RunContextProvider>)>> = RwLock::new(Vec::new());
+
+/// Identifies a provider registered by [`register`], to remove it with [`unregister`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProviderId(u64);
+
+/// Registers a provider in addition to the default providers.
+pub fn register(provider: impl RunContextProvider + 'static) -> ProviderId {
+    let id = ProviderId(NEXT_ID.fetch_add(1, Ordering::SeqCst));
+    PROVIDERS.write().unwrap().push((id, Box::new(provider)));
+    id
+}
+
+/// Removes a provider registered by [`register`].
+///
+/// Returns `false` if the provider has already been removed.
+pub fn unregister(id: ProviderId) -> bool {
+    let mut providers = PROVIDERS.write().unwrap();
+    let len = providers.len();
+    providers.retain(|(i, _)| *i != id);
+    providers.len() != len
+}
+
+/// Enables or disables setting tags from the run context. Enabled by default.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns the tags from all providers, followed by `tags`.
+///
+/// If the same key is returned more than once, the last one is used.
+/// Only `tags` is returned if the run context is disabled.
+pub fn resolve_tags(tags: &[RunTag]) -> Vec<RunTag> {
+    let mut results: Vec<RunTag> = Vec::new();
+    let mut push = |t: RunTag| {
+        results.retain(|r| r.key != t.key);
+        results.push(t);
+    };
+    if ENABLED.load(Ordering::SeqCst) {
+        DefaultRunContext.tags().into_iter().for_each(&mut push);
+        GitRunContext.tags().into_iter().for_each(&mut push);
+        for (_, provider) in PROVIDERS.read().unwrap().iter() {
+            provider.tags().into_iter().for_each(&mut push);
+        }
+    }
+    tags.iter().cloned().for_each(push);
+    results
+}
+
+fn tag(key: &str, value: String) -> RunTag {
+    RunTag {
+        key: key.to_string(),
+        value,
+    }
+}
+
+/// Finds the git directory of the repository containing `dir`.
+fn find_git_dir(dir: &Path) -> Option<PathBuf> {
+    for dir in dir.ancestors() {
+        let path = dir.join(".git");
+        if path.is_dir() {
+            return Some(path);
+        }
+        // Worktrees and submodules have a `.git` file pointing to the git directory.
+        if let Ok(s) = fs::read_to_string(&path) {
+            let git_dir = s.trim().strip_prefix("gitdir: ")?;
+            return Some(dir.join(git_dir));
+        }
+    }
+    None
+}
+
+fn resolve_ref(git_dir: &Path, reference: &str) -> Option<String> {
+    if let Ok(s) = fs::read_to_string(git_dir.join(reference)) {
+        return Some(s.trim().to_string());
+    }
+    let packed = fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| {
+        let (commit, name) = line.split_once(' ')?;
+        (name == reference).then(|| commit.to_string())
+    })
+}
+
+fn origin_url(git_dir: &Path) -> Option<String> {
+    let config = fs::read_to_string(git_dir.join("config")).ok()?;
+    let mut in_origin = false;
+    for line in config.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_origin = line == r#"[remote "origin"]"#;
+        } else if in_origin {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "url" {
+                    return Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,99 @@
+use std::io::Cursor;
+
+use ::image::{
+    codecs::webp::WebPEncoder, DynamicImage, ExtendedColorType, ImageFormat, RgbImage, RgbaImage,
+};
+
+use crate::{
+    data::{Run, Timestamp},
+    utils::random_hex,
+    Error, MlflowRun, Result,
+};
+
+/// Maximum width and height of the thumbnails shown in the image gallery of the MLflow UI.
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// Image logged by [`MlflowRun::log_image`].
+///
+/// This type is available when the `image` feature is enabled.
+#[derive(Debug, Clone, Copy)]
+pub enum Image<'a> {
+    /// Pixels in row-major order with 3 bytes per pixel.
+    Rgb {
+        width: u32,
+        height: u32,
+        data: &'a [u8],
+    },
+    /// Pixels in row-major order with 4 bytes per pixel.
+    Rgba {
+        width: u32,
+        height: u32,
+        data: &'a [u8],
+    },
+    /// Encoded PNG or JPEG.
+    Encoded(&'a [u8]),
+}
+impl Image<'_> {
+    fn decode(&self) -> Result<DynamicImage> {
+        let invalid_size = || Error::from_message("Image data does not match its size");
+        Ok(match *self {
+            Image::Rgb {
+                width,
+                height,
+                data,
+            } => DynamicImage::ImageRgb8(
+                RgbImage::from_raw(width, height, data.to_vec()).ok_or_else(invalid_size)?,
+            ),
+            Image::Rgba {
+                width,
+                height,
+                data,
+            } => DynamicImage::ImageRgba8(
+                RgbaImage::from_raw(width, height, data.to_vec()).ok_or_else(invalid_size)?,
+            ),
+            Image::Encoded(data) => ::image::load_from_memory(data).map_err(Error::from_message)?,
+        })
+    }
+}
+
+/// Uploads `image` and its thumbnail in the layout of `log_image` of the MLflow Python client.
+pub(crate) fn log_image(run: &MlflowRun, image: Image, key: &str, step: i64) -> Result<()> {
+    let image = image.decode()?;
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(Error::from_message)?;
+    let thumbnail = if image.width() > THUMBNAIL_SIZE || image.height() > THUMBNAIL_SIZE {
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    } else {
+        image
+    }
+    .into_rgba8();
+    let mut webp = Vec::new();
+    WebPEncoder::new_lossless(&mut webp)
+        .encode(
+            &thumbnail,
+            thumbnail.width(),
+            thumbnail.height(),
+            ExtendedColorType::Rgba8,
+        )
+        .map_err(Error::from_message)?;
+
+    let id = random_hex(16);
+    let uuid = format!(
+        "{}-{}-{}-{}-{}",
+        &id[..8],
+        &id[8..12],
+        &id[12..16],
+        &id[16..20],
+        &id[20..]
+    );
+    let path = format!(
+        "images/{}%step%{step}%timestamp%{}%{uuid}",
+        key.replace('/', "#"),
+        Timestamp::now().0
+    );
+    run.log_artifact(&format!("{path}.png"), png)?;
+    run.log_artifact(&format!("{path}%compressed.webp"), webp)?;
+    run.set_tag(Run::LOGGED_IMAGES_TAG, "True")
+}
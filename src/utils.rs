@@ -1,3 +1,10 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+    time::SystemTime,
+};
+
 use serde_json::Value;
 
 use crate::data::Param;
@@ -40,3 +47,41 @@ pub(crate) fn build_params(key: &str, value: &Value, params: &mut Vec<Param>) ->
     }
     Ok(())
 }
+
+/// Returns the current Unix time in nanoseconds.
+pub(crate) fn now_nanos() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0)
+}
+
+/// Returns `bytes` random bytes as lowercase hex.
+///
+/// The randomness comes from [`RandomState`] and is not suitable for cryptographic use.
+pub(crate) fn random_hex(bytes: usize) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut s = String::with_capacity(bytes * 2);
+    while s.len() < bytes * 2 {
+        let mut h = RandomState::new().build_hasher();
+        h.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        h.write_i64(now_nanos());
+        s.push_str(&format!("{:016x}", h.finish()));
+    }
+    s.truncate(bytes * 2);
+    s
+}
+
+/// Formats `value` as a YAML document.
+#[cfg(feature = "yaml")]
+pub(crate) fn to_yaml(value: &Value) -> Result<Vec<u8>> {
+    serde_yaml_ng::to_string(value)
+        .map(String::into_bytes)
+        .map_err(Error::from_message)
+}
+#[cfg(not(feature = "yaml"))]
+pub(crate) fn to_yaml(_value: &Value) -> Result<Vec<u8>> {
+    Err(Error::from_message(
+        "Writing YAML requires the `yaml` feature",
+    ))
+}
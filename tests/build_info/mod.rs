@@ -0,0 +1,68 @@
+use std::{fs, path::Path, process::Command};
+
+use anyhow::Result;
+use mlflow_client::build_info::{
+    CARGO_LOCK_ARTIFACT, CARGO_LOCK_SHA256_TAG, CRATE_NAME_TAG, CRATE_VERSION_TAG, FEATURES_TAG,
+    GIT_DIFF_ARTIFACT, GIT_DIRTY_TAG, TARGET_TAG,
+};
+use tempdir::TempDir;
+
+use crate::stand_in::tracking::FakeTracking;
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git").arg("-C").arg(dir).args(args).status()?;
+    anyhow::ensure!(status.success(), "git {args:?} failed");
+    Ok(())
+}
+
+#[test]
+fn log_build_info() -> Result<()> {
+    let dir = TempDir::new("build_info")?;
+    let repo = dir.path();
+    git(repo, &["init", "-q"])?;
+    fs::write(repo.join("Cargo.lock"), "version = 4\n")?;
+    git(repo, &["add", "."])?;
+    git(
+        repo,
+        &[
+            "-c",
+            "user.name=a",
+            "-c",
+            "user.email=a@example.com",
+            "commit",
+            "-qm",
+            "init",
+        ],
+    )?;
+    fs::write(repo.join("Cargo.lock"), "version = 4\n# changed\n")?;
+
+    let mut info = mlflow_client::build_info!();
+    assert_eq!(info.crate_name, "mlflow-client");
+    assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+    info.manifest_dir = repo.to_path_buf();
+    info.target = Some("x86_64-unknown-linux-gnu".to_string());
+    info.features = Some(vec!["a".to_string(), "b".to_string()]);
+    info.cargo_lock = Some(repo.join("Cargo.lock"));
+
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let mut w = e.start_run("run")?;
+    w.log_build_info(&info)?;
+    let run = w.run().clone();
+    w.finish()?;
+
+    let tags = s.state().runs[run.id()].tags.clone();
+    assert_eq!(tags[CRATE_NAME_TAG], "mlflow-client");
+    assert_eq!(tags[CRATE_VERSION_TAG], env!("CARGO_PKG_VERSION"));
+    assert_eq!(tags[TARGET_TAG], "x86_64-unknown-linux-gnu");
+    assert_eq!(tags[FEATURES_TAG], "a,b");
+    assert_eq!(tags[CARGO_LOCK_SHA256_TAG].len(), 64);
+    assert_eq!(tags[GIT_DIRTY_TAG], "true");
+    assert_eq!(
+        run.download_artifact(CARGO_LOCK_ARTIFACT)?,
+        b"version = 4\n# changed\n"
+    );
+    let diff = String::from_utf8(run.download_artifact(GIT_DIFF_ARTIFACT)?)?;
+    assert!(diff.contains("+# changed"));
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use mlflow_client::data::Timestamp;
+use mlflow_client::data::{Run, Timestamp};
+use serde_json::json;
 
 use crate::MlflowServer;
 
@@ -18,3 +19,41 @@ fn timestamp_now() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn run_outputs() -> Result<()> {
+    let run: Run = serde_json::from_value(json!({
+        "info": {
+            "run_id": "r",
+            "run_name": "n",
+            "experiment_id": "0",
+            "status": "FINISHED",
+            "start_time": 1,
+            "artifact_uri": "mlflow-artifacts:/0/r/artifacts",
+            "lifecycle_stage": "active",
+            "user_id": "u",
+        },
+        "data": {
+            "metrics": [{
+                "key": "loss",
+                "value": 0.5,
+                "timestamp": 2,
+                "step": 3,
+                "model_id": "m-1",
+                "dataset_name": "eval",
+                "dataset_digest": "abc",
+            }],
+        },
+        "inputs": { "model_inputs": [{ "model_id": "m-0" }] },
+        "outputs": { "model_outputs": [{ "model_id": "m-1", "step": 3 }] },
+    }))?;
+    assert_eq!(run.info.user_id.as_deref(), Some("u"));
+    let m = &run.data.metrics[0];
+    assert_eq!(m.model_id.as_deref(), Some("m-1"));
+    assert_eq!(m.dataset_name.as_deref(), Some("eval"));
+    assert_eq!(m.dataset_digest.as_deref(), Some("abc"));
+    assert_eq!(run.inputs.model_inputs[0].model_id, "m-0");
+    assert_eq!(run.outputs.model_outputs[0].model_id, "m-1");
+    assert_eq!(run.outputs.model_outputs[0].step, 3);
+    Ok(())
+}
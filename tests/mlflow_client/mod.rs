@@ -2,8 +2,10 @@ use anyhow::Result;
 use mlflow_client::{
     client::MlflowClient,
     data::{
-        CreateRunOptions, Dataset, DatasetInput, InputTag, Metric, Param, RunTag,
-        SearchExperimentsOptions, SearchRunsOptions, UpdateRunOptions, ViewType,
+        CreateLoggedModelOptions, CreateRunOptions, Dataset, DatasetInput, InputTag,
+        LoggedModelParameter, LoggedModelStatus, LoggedModelTag, Metric, Param, RunTag,
+        SearchExperimentsOptions, SearchLoggedModelsOptions, SearchRunsOptions, UpdateRunOptions,
+        ViewType,
     },
 };
 
@@ -271,6 +273,9 @@ fn log_metric() -> Result<()> {
         value: 2.0,
         timestamp: 10.into(),
         step: Some(1),
+        model_id: None,
+        dataset_name: None,
+        dataset_digest: None,
     }));
     Ok(())
 }
@@ -290,6 +295,9 @@ fn log_metric_no_step() -> Result<()> {
         value: 2.0,
         timestamp: 10.into(),
         step: Some(0),
+        model_id: None,
+        dataset_name: None,
+        dataset_digest: None,
     }));
     Ok(())
 }
@@ -327,12 +335,18 @@ fn log_batch() -> Result<()> {
             value: 1.0,
             timestamp: 5.into(),
             step: Some(0),
+            model_id: None,
+            dataset_name: None,
+            dataset_digest: None,
         },
         Metric {
             key: "m2".to_string(),
             value: 2.0,
             timestamp: 10.into(),
             step: Some(1),
+            model_id: None,
+            dataset_name: None,
+            dataset_digest: None,
         },
     ];
     let mut params = vec![
@@ -522,3 +536,118 @@ fn update_run() -> Result<()> {
     assert_eq!(r2.run.info.run_name, "def");
     Ok(())
 }
+
+#[test]
+fn create_logged_model() -> Result<()> {
+    let s = MlflowServer::start();
+    let c = s.mlflow_client();
+    let r0 = c.create_experiment("abc", Default::default())?;
+    let r1 = c.create_run(&r0.experiment_id, "", Default::default())?;
+    let params = vec![LoggedModelParameter {
+        key: "p1".to_string(),
+        value: "v1".to_string(),
+    }];
+    let tags = vec![LoggedModelTag {
+        key: "t1".to_string(),
+        value: "v1".to_string(),
+    }];
+    let r2 = c.create_logged_model(
+        &r0.experiment_id,
+        "model",
+        CreateLoggedModelOptions {
+            model_type: Some("agent"),
+            source_run_id: Some(&r1.run.info.run_id),
+            params: &params,
+            tags: &tags,
+        },
+    )?;
+    let info = &r2.model.info;
+    assert_eq!(info.experiment_id, r0.experiment_id);
+    assert_eq!(info.name, "model");
+    assert_eq!(info.status, LoggedModelStatus::Pending);
+    assert_eq!(info.model_type.as_deref(), Some("agent"));
+    assert_eq!(info.source_run_id.as_deref(), Some(&*r1.run.info.run_id));
+    assert!(info.tags.contains(&tags[0]));
+    assert_eq!(r2.model.data.params, params);
+    Ok(())
+}
+
+#[test]
+fn get_logged_model() -> Result<()> {
+    let s = MlflowServer::start();
+    let c = s.mlflow_client();
+    let r0 = c.create_experiment("abc", Default::default())?;
+    let r1 = c.create_logged_model(&r0.experiment_id, "model", Default::default())?;
+    let model_id = &r1.model.info.model_id;
+    c.finalize_logged_model(model_id, LoggedModelStatus::Ready)?;
+    let r2 = c.get_logged_model(model_id)?;
+    assert_eq!(&r2.model.info.model_id, model_id);
+    assert_eq!(r2.model.info.name, "model");
+    assert_eq!(r2.model.info.status, LoggedModelStatus::Ready);
+    Ok(())
+}
+
+#[test]
+fn get_logged_model_not_found() -> Result<()> {
+    let s = MlflowServer::start();
+    let c = s.mlflow_client();
+    let r = c.get_logged_model("m-aaaaa");
+    assert!(r.unwrap_err().is_resource_does_not_exist());
+    Ok(())
+}
+
+#[test]
+fn search_logged_models() -> Result<()> {
+    let s = MlflowServer::start();
+    let c = s.mlflow_client();
+    let r0 = c.create_experiment("abc", Default::default())?;
+    let r1 = c.create_logged_model(&r0.experiment_id, "model1", Default::default())?;
+    let r2 = c.create_logged_model(&r0.experiment_id, "model2", Default::default())?;
+
+    let options = SearchLoggedModelsOptions {
+        filter: "name = 'model2'",
+        ..Default::default()
+    };
+    let max_results = MlflowClient::SEARCH_LOGGED_MODELS_MAX_RESULTS_SUPPORTED;
+    let r3 = c.search_logged_models(&[&r0.experiment_id], options, max_results, None)?;
+    assert_eq!(r3.models.len(), 1);
+    assert_eq!(r3.models[0].info.model_id, r2.model.info.model_id);
+
+    let options = SearchLoggedModelsOptions::default();
+    let r4 = c.search_logged_models(&[&r0.experiment_id], options, max_results, None)?;
+    let mut ids = r4
+        .models
+        .iter()
+        .map(|m| &m.info.model_id)
+        .collect::<Vec<_>>();
+    ids.sort();
+    let mut expected = vec![&r1.model.info.model_id, &r2.model.info.model_id];
+    expected.sort();
+    assert_eq!(ids, expected);
+    Ok(())
+}
+
+#[test]
+fn log_batch_with_model_and_dataset() -> Result<()> {
+    let s = MlflowServer::start();
+    let c = s.mlflow_client();
+    let r0 = c.create_experiment("abc", Default::default())?;
+    let r1 = c.create_run(&r0.experiment_id, "", Default::default())?;
+    let r2 = c.create_logged_model(&r0.experiment_id, "model", Default::default())?;
+    let run_id = &r1.run.info.run_id;
+    let metric = Metric {
+        key: "m1".to_string(),
+        value: 1.0,
+        timestamp: 5.into(),
+        step: Some(0),
+        model_id: Some(r2.model.info.model_id.clone()),
+        dataset_name: Some("d1".to_string()),
+        dataset_digest: Some("aaa".to_string()),
+    };
+    c.log_batch(run_id, std::slice::from_ref(&metric), &[], &[])?;
+    let r3 = c.get_run(run_id)?;
+    assert!(r3.run.data.metrics.contains(&metric));
+    let r4 = c.get_logged_model(&r2.model.info.model_id)?;
+    assert!(r4.model.data.metrics.contains(&metric));
+    Ok(())
+}
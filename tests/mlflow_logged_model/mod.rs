@@ -0,0 +1,99 @@
+use anyhow::Result;
+use mlflow_client::data::{CreateLoggedModelOptions, LoggedModelStatus, LoggedModelTag};
+
+use crate::stand_in::tracking::FakeTracking;
+
+#[test]
+fn create_and_finalize() -> Result<()> {
+    let s = FakeTracking::start();
+    let m = s.mlflow();
+    let e = m.experiment("0")?.unwrap();
+    let run = e.create_run("run", Default::default())?;
+    let model = run.create_logged_model(
+        "model",
+        CreateLoggedModelOptions {
+            model_type: Some("agent"),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(model.name(), "model");
+    assert_eq!(model.data().info.status, LoggedModelStatus::Pending);
+    assert_eq!(model.data().info.source_run_id.as_deref(), Some(run.id()));
+    assert_eq!(model.data().info.model_type.as_deref(), Some("agent"));
+
+    model.finalize(LoggedModelStatus::Ready)?;
+    let model = m.logged_model(model.id())?.unwrap();
+    assert_eq!(model.data().info.status, LoggedModelStatus::Ready);
+    Ok(())
+}
+
+#[test]
+fn search_and_delete() -> Result<()> {
+    let s = FakeTracking::start();
+    let m = s.mlflow();
+    let e = m.experiment("0")?.unwrap();
+    let model = e.create_logged_model("model", Default::default())?;
+    let models = e.logged_models()?;
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].id(), model.id());
+
+    model.delete()?;
+    assert!(m.logged_model(model.id())?.is_none());
+    Ok(())
+}
+
+#[test]
+fn tags_and_params() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let model = e.create_logged_model("model", Default::default())?;
+    model.set_tag("t1", "v1")?;
+    model.set_tag("t2", "v2")?;
+    model.delete_tag("t1")?;
+
+    #[derive(serde::Serialize)]
+    struct Params {
+        a: i32,
+        b: &'static str,
+    }
+    model.log_params("p", Params { a: 1, b: "x" })?;
+
+    let data = model.reload()?.data().clone();
+    assert_eq!(
+        data.info.tags,
+        vec![LoggedModelTag {
+            key: "t2".to_string(),
+            value: "v2".to_string()
+        }]
+    );
+    let mut params = data
+        .data
+        .params
+        .iter()
+        .map(|p| (p.key.as_str(), p.value.as_str()))
+        .collect::<Vec<_>>();
+    params.sort();
+    assert_eq!(params, [("p.a", "1"), ("p.b", "x")]);
+    Ok(())
+}
+
+#[test]
+fn writer_active_model() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let mut w = e.start_run("run")?;
+    let model = w.run().create_logged_model("model", Default::default())?;
+    w.log_metric("m0", 1.0, Some(0))?;
+    w.set_active_model(Some(model.id()));
+    w.log_metric("m1", 2.0, Some(0))?;
+    let run_id = w.run().id().to_string();
+    w.finish()?;
+
+    let state = s.state();
+    let metrics = &state.runs[&run_id].metrics;
+    let m0 = metrics.iter().find(|m| m["key"] == "m0").unwrap();
+    let m1 = metrics.iter().find(|m| m["key"] == "m1").unwrap();
+    assert!(m0["model_id"].is_null());
+    assert_eq!(m1["model_id"], model.id());
+    Ok(())
+}
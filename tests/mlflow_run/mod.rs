@@ -0,0 +1,164 @@
+use anyhow::Result;
+use mlflow_client::data::Run;
+use serde_json::{json, Value};
+
+use crate::stand_in::tracking::FakeTracking;
+
+#[test]
+fn log_dict() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let w = e.start_run("run")?;
+    let run = w.run().clone();
+    let config = json!({
+        "model": { "name": "resnet-50", "layers": [64, 128] },
+        "lr": 0.001,
+        "tags": ["a b", "true", "x: y"],
+        "empty": {},
+        "note": null,
+    });
+    run.log_dict(&config, "config.json")?;
+    #[cfg(not(feature = "yaml"))]
+    assert!(run.log_dict(&config, "config.yaml").is_err());
+    #[cfg(feature = "yaml")]
+    run.log_dict(&config, "config.yaml")?;
+    w.finish()?;
+
+    let json: Value = serde_json::from_slice(&run.download_artifact("config.json")?)?;
+    assert_eq!(json, config);
+    #[cfg(feature = "yaml")]
+    assert_eq!(
+        String::from_utf8(run.download_artifact("config.yaml")?)?,
+        r#"empty: {}
+lr: 0.001
+model:
+  layers:
+  - 64
+  - 128
+  name: resnet-50
+note: null
+tags:
+- a b
+- 'true'
+- 'x: y'
+"#
+    );
+    Ok(())
+}
+
+fn table(run: &mlflow_client::MlflowRun, path: &str) -> Result<Value> {
+    Ok(serde_json::from_slice(&run.download_artifact(path)?)?)
+}
+
+#[test]
+fn log_table() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let w = e.start_run("run")?;
+    let run = w.run().clone();
+    run.log_table(
+        &["input", "output"],
+        &[
+            vec![json!("1+1"), json!("2")],
+            vec![json!("2+2"), json!("4")],
+        ],
+        "eval/results.json",
+    )?;
+    assert_eq!(
+        table(&run, "eval/results.json")?,
+        json!({
+            "columns": ["input", "output"],
+            "data": [["1+1", "2"], ["2+2", "4"]],
+        })
+    );
+    run.log_table(
+        &["output", "score"],
+        &[vec![json!("6"), json!(0.5)]],
+        "eval/results.json",
+    )?;
+    assert_eq!(
+        table(&run, "eval/results.json")?,
+        json!({
+            "columns": ["input", "output", "score"],
+            "data": [["1+1", "2", null], ["2+2", "4", null], [null, "6", 0.5]],
+        })
+    );
+    assert!(run
+        .log_table(&["a"], &[vec![json!(1), json!(2)]], "t.json")
+        .is_err());
+    assert!(run.log_table(&["a"], &[vec![json!(1)]], "t.csv").is_err());
+    w.finish()?;
+
+    let tags = &s.state().runs[run.id()].tags;
+    let logged: Value = serde_json::from_str(&tags[Run::LOGGED_ARTIFACTS_TAG])?;
+    assert_eq!(
+        logged,
+        json!([{ "path": "eval/results.json", "type": "table" }])
+    );
+    Ok(())
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn log_image() -> Result<()> {
+    use mlflow_client::Image;
+
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let w = e.start_run("run")?;
+    let run = w.run().clone();
+    let rgb = vec![128; 300 * 100 * 3];
+    run.log_image(
+        Image::Rgb {
+            width: 300,
+            height: 100,
+            data: &rgb,
+        },
+        "eval/confusion",
+        Some(3),
+    )?;
+    let mut png = Vec::new();
+    image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+    run.log_image(Image::Encoded(&png), "sample", None)?;
+    let invalid = Image::Rgba {
+        width: 2,
+        height: 2,
+        data: &[0; 15],
+    };
+    assert!(run.log_image(invalid, "invalid", None).is_err());
+    assert!(run
+        .log_image(Image::Encoded(b"not an image"), "invalid", None)
+        .is_err());
+    w.finish()?;
+
+    let prefix = format!("0/{}/artifacts/", run.id());
+    let artifacts = s
+        .state()
+        .artifacts
+        .keys()
+        .filter_map(|a| a.strip_prefix(&prefix).map(|a| a.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(artifacts.len(), 4);
+    let image_size = |key: &str, step: i64, ext: &str| -> Result<(u32, u32)> {
+        let start = format!("images/{key}%step%{step}%timestamp%");
+        let path = artifacts
+            .iter()
+            .find(|a| a.starts_with(&start) && a.ends_with(ext))
+            .unwrap();
+        let image = image::load_from_memory(&run.download_artifact(path)?)?;
+        Ok((image.width(), image.height()))
+    };
+    assert_eq!(image_size("eval#confusion", 3, ".png")?, (300, 100));
+    assert_eq!(
+        image_size("eval#confusion", 3, "%compressed.webp")?,
+        (256, 85)
+    );
+    assert_eq!(image_size("sample", 0, ".png")?, (2, 2));
+    assert_eq!(image_size("sample", 0, "%compressed.webp")?, (2, 2));
+    assert_eq!(
+        s.state().runs[run.id()].tags[Run::LOGGED_IMAGES_TAG],
+        "True"
+    );
+    Ok(())
+}
@@ -0,0 +1,1397 @@
+use std::{
+    fs,
+    sync::mpsc,
+    thread::{sleep, spawn},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use mlflow_client::{
+    data::Dataset, Aggregation, AggregationWindow, CheckpointPolicy, ErrorPolicy, KeepBest,
+    MetricGoal, MlflowRunWriterOptions, MlflowRunWriterStats, OutputCapture, QueueFullPolicy,
+    Reduction, StepMode, TrackProgress, HEARTBEAT_TAG, OUTPUT_ARTIFACT, PANIC_MESSAGE_TAG,
+    PROGRESS_TAG,
+};
+
+use tempdir::TempDir;
+
+use crate::stand_in::tracking::FakeTracking;
+
+fn log_batch_count(s: &FakeTracking) -> usize {
+    s.requests()
+        .iter()
+        .filter(|r| r.path.ends_with("/runs/log-batch"))
+        .count()
+}
+
+/// Returns the steps and values of the metrics received for the run, limited to `key` if specified.
+fn history(s: &FakeTracking, run_id: &str, key: Option<&str>) -> Vec<(Option<i64>, f64)> {
+    s.state().runs[run_id]
+        .metrics
+        .iter()
+        .filter(|m| key.is_none_or(|key| m["key"] == key))
+        .map(|m| (m["step"].as_i64(), m["value"].as_f64().unwrap()))
+        .collect()
+}
+
+fn start_with(
+    s: &FakeTracking,
+    options: MlflowRunWriterOptions,
+) -> Result<mlflow_client::MlflowRunWriter> {
+    let e = s.mlflow().experiment("0")?.unwrap();
+    Ok(e.start_run_with_writer_options("run", Default::default(), options)?)
+}
+
+/// Polls `f` until it returns true, failing the test if it takes longer than 5 seconds.
+fn wait_until(f: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !f() {
+        assert!(Instant::now() < deadline, "timed out");
+        sleep(Duration::from_millis(5));
+    }
+}
+
+fn dataset(name: &str) -> Dataset {
+    Dataset {
+        name: name.to_string(),
+        digest: format!("{name}-digest"),
+        source_type: "local".to_string(),
+        source: "file:///data".to_string(),
+        schema: None,
+        profile: None,
+    }
+}
+
+#[test]
+fn log_metric_for_dataset() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(&s, Default::default())?;
+    let run = w.run().clone();
+    w.log_metric("loss", 1.0, Some(0))?;
+    w.log_metric_for_dataset("loss", 2.0, Some(0), &dataset("eval"))?;
+    w.log_metrics_for_dataset(&[("acc", 0.5)], Some(0), &dataset("test"))?;
+    w.finish()?;
+
+    let history = run.metric_history("loss")?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].dataset_name, None);
+    assert_eq!(history[1].dataset_name.as_deref(), Some("eval"));
+    assert_eq!(history[1].dataset_digest.as_deref(), Some("eval-digest"));
+    let history = run.metric_history("acc")?;
+    assert_eq!(history[0].dataset_name.as_deref(), Some("test"));
+    Ok(())
+}
+
+#[test]
+fn logger_from_threads() -> Result<()> {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let s = FakeTracking::start();
+    let w = start_with(&s, Default::default())?;
+    let run = w.run().clone();
+    let logger = w.logger();
+    assert_send_sync(&logger);
+    std::thread::scope(|scope| -> Result<()> {
+        let mut handles = Vec::new();
+        for rank in 0..4 {
+            let logger = logger.clone();
+            handles.push(scope.spawn(move || -> Result<()> {
+                logger.set_tag(&format!("rank_{rank}"), "ready")?;
+                logger.log_param(&format!("seed_{rank}"), &rank.to_string())?;
+                for step in 0..10 {
+                    logger.log_metric("loss", step as f64, Some(step))?;
+                }
+                Ok(())
+            }));
+        }
+        for h in handles {
+            h.join().unwrap()?;
+        }
+        Ok(())
+    })?;
+    w.finish()?;
+
+    assert_eq!(history(&s, run.id(), Some("loss")).len(), 40);
+    let state = s.state();
+    let r = &state.runs[run.id()];
+    assert_eq!(r.params.len(), 4);
+    assert_eq!(r.tags.get("rank_3").map(|v| v.as_str()), Some("ready"));
+    drop(state);
+    assert!(logger.log_metric("loss", 0.0, None).is_err());
+    assert!(logger.set_tag("late", "1").is_err());
+    Ok(())
+}
+
+#[test]
+fn params_and_tags_are_batched() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(&s, Default::default())?;
+    let run = w.run().clone();
+    let params = (0..250)
+        .map(|i| (format!("p{i}"), i))
+        .collect::<std::collections::BTreeMap<_, _>>();
+    w.log_params("hp", &params)?;
+    w.log_param("lr", "0.1")?;
+    w.log_param("lr", "0.1")?;
+    assert!(w.log_param("lr", "0.2").is_err());
+    w.set_tag("phase", "train")?;
+    w.set_tag("phase", "eval")?;
+    w.log_metric("loss", 1.0, Some(0))?;
+    w.finish()?;
+
+    let state = s.state();
+    let r = &state.runs[run.id()];
+    assert_eq!(r.params.len(), 251);
+    assert!(r
+        .params
+        .contains(&("hp.p249".to_string(), "249".to_string())));
+    assert_eq!(r.tags.get("phase").map(|v| v.as_str()), Some("eval"));
+    assert_eq!(r.metrics.len(), 1);
+    let requests = s.requests();
+    assert!(requests
+        .iter()
+        .all(|r| !r.path.ends_with("/runs/log-parameter") && !r.path.ends_with("/runs/set-tag")));
+    for r in requests
+        .iter()
+        .filter(|r| r.path.ends_with("/runs/log-batch"))
+    {
+        let body = r.json();
+        assert!(body["params"].as_array().map_or(0, |a| a.len()) <= 100);
+    }
+    Ok(())
+}
+
+#[test]
+fn max_batch_size() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            max_batch_size: 10,
+            ..Default::default()
+        },
+    )?;
+    let metrics = (0..25).map(|i| (format!("m{i}"), 0.0)).collect::<Vec<_>>();
+    w.log_metrics(&metrics, Some(0))?;
+    w.flush()?;
+    assert_eq!(log_batch_count(&s), 3);
+    w.finish()?;
+    Ok(())
+}
+
+#[test]
+fn flush_interval() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            flush_interval: Duration::from_secs(30),
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    w.log_metric("loss", 0.0, Some(0))?;
+    w.flush()?;
+    assert_eq!(log_batch_count(&s), 1);
+    for i in 1..20 {
+        w.log_metric("loss", i as f64, Some(i))?;
+    }
+    assert_eq!(log_batch_count(&s), 1);
+    w.flush()?;
+    assert_eq!(log_batch_count(&s), 2);
+    assert_eq!(history(&s, &run_id, None).len(), 20);
+
+    let start = Instant::now();
+    w.log_metric("loss", 20.0, Some(20))?;
+    w.finish()?;
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(history(&s, &run_id, None).len(), 21);
+    Ok(())
+}
+
+/// Sends one metric and leaves four metrics in the queue bounded to five.
+fn fill_queue(w: &mut mlflow_client::MlflowRunWriter) -> Result<()> {
+    w.log_metric("m", 0.0, None)?;
+    w.flush()?;
+    w.log_metrics(&[("m", 1.0), ("m", 2.0), ("m", 3.0), ("m", 4.0)], None)?;
+    Ok(())
+}
+
+#[test]
+fn queue_full_error() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            flush_interval: Duration::from_secs(30),
+            max_queue_size: Some(5),
+            queue_full_policy: QueueFullPolicy::Error,
+            ..Default::default()
+        },
+    )?;
+    fill_queue(&mut w)?;
+    let run_id = w.run().id().to_string();
+    assert!(w.log_metrics(&[("m", 5.0), ("m", 6.0)], None).is_err());
+    w.log_metric("m", 5.0, None)?;
+    w.finish()?;
+    assert_eq!(
+        history(&s, &run_id, None),
+        [
+            (None, 0.0),
+            (None, 1.0),
+            (None, 2.0),
+            (None, 3.0),
+            (None, 4.0),
+            (None, 5.0)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn queue_full_drop_oldest() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            flush_interval: Duration::from_secs(30),
+            max_queue_size: Some(5),
+            queue_full_policy: QueueFullPolicy::DropOldest,
+            ..Default::default()
+        },
+    )?;
+    fill_queue(&mut w)?;
+    let run_id = w.run().id().to_string();
+    w.log_metrics(&[("m", 5.0), ("m", 6.0), ("m", 7.0)], None)?;
+    w.finish()?;
+    assert_eq!(
+        history(&s, &run_id, None),
+        [
+            (None, 0.0),
+            (None, 3.0),
+            (None, 4.0),
+            (None, 5.0),
+            (None, 6.0),
+            (None, 7.0)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn queue_full_block() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            flush_interval: Duration::from_secs(30),
+            max_queue_size: Some(5),
+            queue_full_policy: QueueFullPolicy::Block,
+            ..Default::default()
+        },
+    )?;
+    fill_queue(&mut w)?;
+    let run_id = w.run().id().to_string();
+    let start = Instant::now();
+    w.log_metrics(&[("m", 5.0), ("m", 6.0), ("m", 7.0)], None)?;
+    assert!(start.elapsed() < Duration::from_secs(10));
+    w.finish()?;
+    assert_eq!(
+        history(&s, &run_id, None),
+        [
+            (None, 0.0),
+            (None, 1.0),
+            (None, 2.0),
+            (None, 3.0),
+            (None, 4.0),
+            (None, 5.0),
+            (None, 6.0),
+            (None, 7.0)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn retry_transient_error() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            retry_interval: Duration::from_millis(10),
+            error_policy: ErrorPolicy::BestEffort,
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    s.state().log_batch_errors = vec!["TEMPORARILY_UNAVAILABLE"; 2];
+    w.log_metric("m", 1.0, None)?;
+    w.flush()?;
+    assert_eq!(
+        w.stats(),
+        MlflowRunWriterStats {
+            sent: 1,
+            dropped: 0,
+            failed: 0
+        }
+    );
+    w.finish()?;
+    assert_eq!(history(&s, &run_id, None), [(None, 1.0)]);
+    Ok(())
+}
+
+#[test]
+fn best_effort_continues_after_error() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            retry_interval: Duration::from_millis(10),
+            error_policy: ErrorPolicy::BestEffort,
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    s.state().log_batch_errors = vec!["INVALID_PARAMETER_VALUE"];
+    w.log_metric("m", 1.0, None)?;
+    assert!(w.flush().is_err());
+    w.log_metric("m", 2.0, None)?;
+    w.flush()?;
+    assert_eq!(
+        w.stats(),
+        MlflowRunWriterStats {
+            sent: 1,
+            dropped: 0,
+            failed: 1
+        }
+    );
+    w.finish()?;
+    assert_eq!(history(&s, &run_id, None), [(None, 2.0)]);
+    Ok(())
+}
+
+#[test]
+fn fail_fast_stops_after_error() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            retry_interval: Duration::from_millis(10),
+            error_policy: ErrorPolicy::FailFast,
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    s.state().log_batch_errors = vec!["INVALID_PARAMETER_VALUE"];
+    w.log_metric("m", 1.0, None)?;
+    assert!(w.flush().is_err());
+    assert!(w.log_metric("m", 2.0, None).is_err());
+    assert_eq!(w.stats().failed, 1);
+    w.finish()?;
+    assert!(history(&s, &run_id, None).is_empty());
+    assert_eq!(s.state().runs[&run_id].info["status"], "FINISHED");
+    Ok(())
+}
+
+#[test]
+fn journal_is_removed_after_finish() -> Result<()> {
+    let s = FakeTracking::start();
+    let dir = TempDir::new("journal")?;
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            max_retries: 0,
+            retry_interval: Duration::from_millis(10),
+            journal_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        },
+    )?;
+    let path = dir.path().join(format!("{}.jsonl", w.run().id()));
+    w.log_metric("m", 1.0, None)?;
+    w.flush()?;
+    assert!(path.exists());
+    w.finish()?;
+    assert!(!path.exists());
+    Ok(())
+}
+
+#[test]
+fn journal_keeps_logs_during_outage() -> Result<()> {
+    let s = FakeTracking::start();
+    let dir = TempDir::new("journal")?;
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            max_retries: 0,
+            retry_interval: Duration::from_millis(10),
+            journal_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    s.state().log_batch_errors = vec!["TEMPORARILY_UNAVAILABLE"; 3];
+    w.log_metric("m", 1.0, None)?;
+    assert!(w.flush().is_err());
+    let journal = fs::read_to_string(dir.path().join(format!("{run_id}.jsonl")))?;
+    assert_eq!(journal.lines().count(), 1);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while w.flush().is_err() {
+        assert!(Instant::now() < deadline);
+        sleep(Duration::from_millis(10));
+    }
+    assert_eq!(w.stats().failed, 0);
+    w.finish()?;
+    assert_eq!(history(&s, &run_id, None), [(None, 1.0)]);
+    assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn replay_journals() -> Result<()> {
+    let s = FakeTracking::start();
+    let dir = TempDir::new("journal")?;
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            max_retries: 0,
+            retry_interval: Duration::from_millis(10),
+            journal_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    s.state().log_batch_errors = vec!["TEMPORARILY_UNAVAILABLE"; 100];
+    w.log_param("lr", "0.1")?;
+    w.log_metric("m", 1.0, None)?;
+    w.log_metric("m", 2.0, None)?;
+    assert!(w.finish().is_err());
+    assert!(history(&s, &run_id, None).is_empty());
+
+    s.state().log_batch_errors.clear();
+    assert_eq!(s.mlflow().replay_journals(dir.path())?, [run_id.as_str()]);
+    assert_eq!(history(&s, &run_id, None), [(None, 1.0), (None, 2.0)]);
+    assert_eq!(
+        s.state().runs[&run_id].params,
+        [("lr".into(), "0.1".into())]
+    );
+    assert_eq!(s.state().runs[&run_id].info["status"], "FINISHED");
+    assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+    assert!(s.mlflow().replay_journals(dir.path())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn replay_skips_acknowledged_logs() -> Result<()> {
+    let s = FakeTracking::start();
+    let dir = TempDir::new("journal")?;
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            max_batch_size: 1,
+            max_retries: 0,
+            retry_interval: Duration::from_millis(10),
+            journal_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    s.state().log_batch_errors_after = 1;
+    s.state().log_batch_errors = vec!["TEMPORARILY_UNAVAILABLE"; 100];
+    w.log_metrics(&[("a", 1.0), ("b", 2.0), ("c", 3.0)], None)?;
+    assert!(w.finish().is_err());
+    assert_eq!(history(&s, &run_id, None), [(None, 1.0)]);
+
+    s.state().log_batch_errors.clear();
+    s.mlflow().replay_journals(dir.path())?;
+    assert_eq!(
+        history(&s, &run_id, None),
+        [(None, 1.0), (None, 2.0), (None, 3.0)]
+    );
+    Ok(())
+}
+
+#[test]
+fn block_does_not_wait_during_outage() -> Result<()> {
+    let s = FakeTracking::start();
+    let dir = TempDir::new("journal")?;
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            max_queue_size: Some(2),
+            queue_full_policy: QueueFullPolicy::Block,
+            max_retries: 0,
+            retry_interval: Duration::from_millis(10),
+            journal_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    s.state().log_batch_errors = vec!["TEMPORARILY_UNAVAILABLE"; 1000];
+    let logger = w.logger();
+    let (sender, receiver) = mpsc::channel();
+    spawn(move || {
+        for i in 0..10 {
+            logger.log_metric("m", i as f64, Some(i)).unwrap();
+        }
+        sender.send(()).unwrap();
+    });
+    receiver.recv_timeout(Duration::from_secs(10))?;
+
+    s.state().log_batch_errors.clear();
+    w.finish()?;
+    assert_eq!(history(&s, &run_id, None).len(), 10);
+    Ok(())
+}
+
+#[test]
+fn drop_without_finish_fails_run() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(&s, Default::default())?;
+    let run_id = w.run().id().to_string();
+    drop(w);
+    let state = s.state();
+    let run = &state.runs[&run_id];
+    assert_eq!(run.info["status"], "FAILED");
+    assert!(!run.tags.contains_key(PANIC_MESSAGE_TAG));
+    Ok(())
+}
+
+#[test]
+fn panic_message_is_recorded() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            capture_panic_message: true,
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    let r = spawn(move || {
+        let _w = w;
+        panic!("training diverged");
+    })
+    .join();
+    assert!(r.is_err());
+    let state = s.state();
+    let run = &state.runs[&run_id];
+    assert_eq!(run.info["status"], "FAILED");
+    assert!(run.tags[PANIC_MESSAGE_TAG].starts_with("training diverged"));
+    Ok(())
+}
+
+#[test]
+fn panic_message_is_not_recorded_by_default() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(&s, Default::default())?;
+    let run_id = w.run().id().to_string();
+    let r = spawn(move || {
+        let _w = w;
+        panic!("training diverged");
+    })
+    .join();
+    assert!(r.is_err());
+    let state = s.state();
+    let run = &state.runs[&run_id];
+    assert_eq!(run.info["status"], "FAILED");
+    assert!(!run.tags.contains_key(PANIC_MESSAGE_TAG));
+    Ok(())
+}
+
+/// Environment variable with the URI of the stand-in server, set when a child test runs in a subprocess.
+#[cfg(unix)]
+const CHILD_URI: &str = "MLFLOW_CLIENT_TEST_CHILD_URI";
+
+/// Returns a command that runs the test `name` of this module in a subprocess,
+/// for tests that change the state of the whole process.
+#[cfg(unix)]
+fn child_test(name: &str, s: &FakeTracking) -> Result<std::process::Command> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .args([
+            "--exact",
+            &format!("mlflow_run_writer::{name}"),
+            "--nocapture",
+        ])
+        .env(CHILD_URI, s.uri());
+    Ok(command)
+}
+
+/// Logs to a run with `handle_signals` and waits for a signal. Does nothing unless run by [`signal_ends_run`].
+#[cfg(all(unix, feature = "signals"))]
+#[test]
+fn signal_child() -> Result<()> {
+    let Ok(uri) = std::env::var(CHILD_URI) else {
+        return Ok(());
+    };
+    let e = mlflow_client::Mlflow::new(&uri)?.experiment("0")?.unwrap();
+    let options = MlflowRunWriterOptions {
+        flush_interval: Duration::from_secs(3600),
+        handle_signals: true,
+        ..Default::default()
+    };
+    let mut w = e.start_run_with_writer_options("run", Default::default(), options)?;
+    w.log_metric("m", 1.0, None)?;
+    w.flush()?;
+    // Kept pending by `flush_interval` until the signal.
+    w.log_metric("m", 2.0, None)?;
+    println!("ready");
+    sleep(Duration::from_secs(60));
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "signals"))]
+#[test]
+fn signal_ends_run() -> Result<()> {
+    use std::{
+        io::{BufRead, BufReader},
+        os::unix::process::ExitStatusExt,
+        process::{Command, Stdio},
+    };
+
+    let s = FakeTracking::start();
+    let mut child = child_test("signal_child", &s)?
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut ready = false;
+    for line in stdout.lines() {
+        // The line may start with the name of the test printed by the test harness.
+        if line?.ends_with("ready") {
+            ready = true;
+            break;
+        }
+    }
+    assert!(ready);
+    Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()?;
+    let status = child.wait()?;
+    assert_eq!(status.signal(), Some(15));
+
+    let run_id = s.state().runs.keys().next().unwrap().clone();
+    assert_eq!(s.state().runs[&run_id].info["status"], "KILLED");
+    assert_eq!(history(&s, &run_id, None), [(None, 1.0), (None, 2.0)]);
+    Ok(())
+}
+
+#[test]
+fn resume_run() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(&s, Default::default())?;
+    let run_id = w.run().id().to_string();
+    w.log_param("lr", "0.1")?;
+    for step in 0..3 {
+        w.log_metric("loss", step as f64, Some(step))?;
+    }
+    w.finish()?;
+
+    assert_ne!(s.state().runs[&run_id].info["end_time"], 0);
+    let mut w = s.mlflow().resume_run(&run_id)?;
+    assert_eq!(s.state().runs[&run_id].info["status"], "RUNNING");
+    assert_eq!(s.state().runs[&run_id].info["end_time"], 0);
+    assert_eq!(w.run().data().info.end_time, None);
+    assert_eq!(w.last_step("loss"), Some(2));
+    assert_eq!(w.last_step("acc"), None);
+    w.log_param("lr", "0.1")?;
+    assert!(w.log_param("lr", "0.2").is_err());
+    w.log_metric("loss", 3.0, Some(w.last_step("loss").unwrap() + 1))?;
+    w.finish()?;
+    assert_eq!(
+        history(&s, &run_id, None),
+        [
+            (Some(0), 0.0),
+            (Some(1), 1.0),
+            (Some(2), 2.0),
+            (Some(3), 3.0)
+        ]
+    );
+    assert_eq!(s.state().runs[&run_id].info["status"], "FINISHED");
+    assert_ne!(s.state().runs[&run_id].info["end_time"], 0);
+    Ok(())
+}
+
+#[test]
+fn resume_run_from() -> Result<()> {
+    let s = FakeTracking::start();
+    let run_id = start_with(&s, Default::default())?.run().id().to_string();
+
+    assert!(s.mlflow().resume_run_from(None)?.is_none());
+    assert!(s.mlflow().resume_run_from(Some(""))?.is_none());
+    let w = s.mlflow().resume_run_from(Some(&run_id))?.unwrap();
+    assert_eq!(w.run().id(), run_id);
+    w.finish()?;
+    assert_eq!(s.state().runs[&run_id].info["status"], "FINISHED");
+    Ok(())
+}
+
+#[test]
+fn resume_run_restores_status_on_error() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(&s, Default::default())?;
+    let run_id = w.run().id().to_string();
+    w.finish()?;
+    let dir = TempDir::new("journal")?;
+    let file = dir.path().join("file");
+    fs::write(&file, "")?;
+
+    let options = MlflowRunWriterOptions {
+        journal_dir: Some(file),
+        ..Default::default()
+    };
+    assert!(s
+        .mlflow()
+        .resume_run_with_writer_options(&run_id, options)
+        .is_err());
+    assert_eq!(s.state().runs[&run_id].info["status"], "FINISHED");
+    Ok(())
+}
+
+#[test]
+fn start_child_run() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let parent = start_with(&s, Default::default())?;
+    let mut children = Vec::new();
+    for i in 0..2 {
+        let mut child = parent.start_child_run(&format!("trial-{i}"))?;
+        child.log_metric("loss", i as f64, None)?;
+        children.push(child.run().id().to_string());
+        child.finish()?;
+    }
+    let parent_run = parent.run().clone();
+    parent.finish()?;
+
+    assert_eq!(parent_run.parent_id(), None);
+    assert!(parent_run.parent()?.is_none());
+    let mut ids = parent_run
+        .children()?
+        .iter()
+        .map(|r| r.id().to_string())
+        .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, children);
+    let child = e.run(&children[0])?.unwrap();
+    assert_eq!(child.parent_id(), Some(parent_run.id()));
+    assert_eq!(child.parent()?.unwrap().id(), parent_run.id());
+    assert!(child.children()?.is_empty());
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn system_metrics() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            system_metrics_interval: Some(Duration::from_millis(20)),
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    let cpu = "system/cpu_utilization_percentage";
+    wait_until(|| history(&s, run.id(), Some(cpu)).len() >= 2);
+    w.finish()?;
+    let count = s.state().runs[run.id()].metrics.len();
+
+    let history = run.metric_history(cpu)?;
+    assert_eq!(history[0].step, Some(0));
+    assert_eq!(history[1].step, Some(1));
+    assert!(history.iter().all(|m| (0.0..=100.0).contains(&m.value)));
+    let memory = run.metric_history("system/system_memory_usage_megabytes")?;
+    assert!(memory.iter().all(|m| m.value > 0.0));
+    sleep(Duration::from_millis(50));
+    assert_eq!(s.state().runs[run.id()].metrics.len(), count);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn system_metrics_continue_steps_on_resume() -> Result<()> {
+    let s = FakeTracking::start();
+    let options = MlflowRunWriterOptions {
+        system_metrics_interval: Some(Duration::from_millis(20)),
+        ..Default::default()
+    };
+    let key = "system/system_memory_usage_megabytes";
+    let w = start_with(&s, options.clone())?;
+    let run = w.run().clone();
+    wait_until(|| !history(&s, run.id(), Some(key)).is_empty());
+    w.finish()?;
+    let first = history(&s, run.id(), Some(key)).len();
+    let w = s
+        .mlflow()
+        .resume_run_with_writer_options(run.id(), options)?;
+    wait_until(|| history(&s, run.id(), Some(key)).len() > first);
+    w.finish()?;
+
+    let steps = run
+        .metric_history(key)?
+        .iter()
+        .map(|m| m.step.unwrap())
+        .collect::<Vec<_>>();
+    assert!(steps.len() > first);
+    // The resumed writer starts right after the first writer's last step.
+    assert_eq!(steps[first], steps[first - 1] + 1);
+    assert_eq!(steps, (0..steps.len() as i64).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn step_mode_per_key() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            step_mode: StepMode::PerKey,
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    w.log_metric("a", 0.0, None)?;
+    w.log_metric("a", 0.0, None)?;
+    w.log_metric("b", 0.0, None)?;
+    w.log_metric("a", 0.0, Some(10))?;
+    w.log_metric("a", 0.0, None)?;
+    w.finish()?;
+    assert_eq!(
+        history(&s, run.id(), Some("a")),
+        [
+            (Some(0), 0.0),
+            (Some(1), 0.0),
+            (Some(10), 0.0),
+            (Some(11), 0.0)
+        ]
+    );
+    assert_eq!(history(&s, run.id(), Some("b")), [(Some(0), 0.0)]);
+    Ok(())
+}
+
+#[test]
+fn step_mode_global() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            step_mode: StepMode::Global,
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    w.log_metrics(&[("a", 0.0), ("b", 0.0)], None)?;
+    assert_eq!(w.advance_step(), 1);
+    w.log_metric("a", 0.0, None)?;
+    w.set_step(5);
+    assert_eq!(w.step(), 5);
+    w.logger().log_metric("b", 0.0, None)?;
+    w.finish()?;
+    assert_eq!(
+        history(&s, run.id(), Some("a")),
+        [(Some(0), 0.0), (Some(1), 0.0)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("b")),
+        [(Some(0), 0.0), (Some(5), 0.0)]
+    );
+    Ok(())
+}
+
+#[test]
+fn scope() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            step_mode: StepMode::PerKey,
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    for epoch in 0..2 {
+        let mut scope = w.scope("epoch");
+        scope.log_metric("loss", 1.0 + epoch as f64)?;
+        scope.log_metric("loss", 3.0 + epoch as f64)?;
+        scope.report("acc", 0.5);
+        if epoch == 0 {
+            scope.finish()?;
+        }
+    }
+    w.finish()?;
+    assert_eq!(
+        history(&s, run.id(), Some("loss")),
+        [
+            (Some(0), 1.0),
+            (Some(1), 3.0),
+            (Some(2), 2.0),
+            (Some(3), 4.0)
+        ]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("epoch/loss")),
+        [(Some(0), 2.0), (Some(1), 3.0)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("epoch/acc")),
+        [(Some(0), 0.5), (Some(1), 0.5)]
+    );
+    let elapsed = history(&s, run.id(), Some("epoch/elapsed_seconds"));
+    assert_eq!(elapsed.len(), 2);
+    assert!(elapsed.iter().all(|(_, value)| *value >= 0.0));
+
+    let w = s.mlflow().resume_run(run.id())?;
+    w.scope("epoch").finish()?;
+    w.finish()?;
+    let elapsed = history(&s, run.id(), Some("epoch/elapsed_seconds"));
+    assert_eq!(
+        elapsed.iter().map(|(step, _)| *step).collect::<Vec<_>>(),
+        [Some(0), Some(1), Some(2)]
+    );
+    Ok(())
+}
+
+#[test]
+fn aggregation_count_window() -> Result<()> {
+    let s = FakeTracking::start();
+    let loss = Aggregation {
+        reductions: vec![
+            Reduction::Mean,
+            Reduction::Min,
+            Reduction::Max,
+            Reduction::Last,
+            Reduction::Percentile(50.0),
+            Reduction::Ema(0.5),
+            Reduction::Ema(0.25),
+        ],
+        window: AggregationWindow::Count(4),
+    };
+    let acc = Aggregation {
+        reductions: vec![Reduction::Mean],
+        window: AggregationWindow::Count(2),
+    };
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            aggregations: [("loss".to_string(), loss), ("acc".to_string(), acc)].into(),
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    for (step, value) in [4.0, 1.0, 3.0, 2.0, 10.0].into_iter().enumerate() {
+        w.log_metrics(
+            &[("loss", value), ("acc", value), ("lr", value)],
+            Some(step as i64),
+        )?;
+    }
+    w.finish()?;
+
+    assert_eq!(
+        history(&s, run.id(), Some("loss/mean")),
+        [(Some(3), 2.5), (Some(4), 10.0)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("loss/min")),
+        [(Some(3), 1.0), (Some(4), 10.0)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("loss/max")),
+        [(Some(3), 4.0), (Some(4), 10.0)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("loss/last")),
+        [(Some(3), 2.0), (Some(4), 10.0)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("loss/p50")),
+        [(Some(3), 2.0), (Some(4), 10.0)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("loss/ema0.5")),
+        [(Some(3), 2.375), (Some(4), 6.1875)]
+    );
+    assert_eq!(
+        history(&s, run.id(), Some("loss/ema0.25")),
+        [(Some(3), 2.890625), (Some(4), 4.66796875)]
+    );
+    assert!(history(&s, run.id(), Some("loss")).is_empty());
+    assert_eq!(
+        history(&s, run.id(), Some("acc")),
+        [(Some(1), 2.5), (Some(3), 2.5), (Some(4), 10.0)]
+    );
+    assert_eq!(history(&s, run.id(), Some("lr")).len(), 5);
+    Ok(())
+}
+
+#[test]
+fn aggregation_duration_window() -> Result<()> {
+    let s = FakeTracking::start();
+    let loss = Aggregation {
+        reductions: vec![Reduction::Max],
+        window: AggregationWindow::Duration(Duration::from_secs(1)),
+    };
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            aggregations: [("loss".to_string(), loss)].into(),
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    w.log_metric("loss", 1.0, Some(0))?;
+    w.log_metric("loss", 2.0, Some(1))?;
+    sleep(Duration::from_millis(1100));
+    w.log_metric("loss", 0.5, Some(2))?;
+    w.flush()?;
+    assert_eq!(history(&s, run.id(), Some("loss")), [(Some(1), 2.0)]);
+    w.finish()?;
+    assert_eq!(
+        history(&s, run.id(), Some("loss")),
+        [(Some(1), 2.0), (Some(2), 0.5)]
+    );
+    Ok(())
+}
+
+#[test]
+fn heartbeat() -> Result<()> {
+    let s = FakeTracking::start();
+    let dir = TempDir::new("journal")?;
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            heartbeat_interval: Some(Duration::from_millis(20)),
+            journal_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        },
+    )?;
+    let run_id = w.run().id().to_string();
+    let heartbeat = |s: &FakeTracking| -> Option<i64> {
+        s.state().runs[&run_id]
+            .tags
+            .get(HEARTBEAT_TAG)?
+            .parse()
+            .ok()
+    };
+    wait_until(|| heartbeat(&s).is_some());
+    let first = heartbeat(&s).unwrap();
+    wait_until(|| heartbeat(&s).unwrap() > first);
+    let journal = fs::read_to_string(dir.path().join(format!("{run_id}.jsonl")))?;
+    assert!(!journal.contains(HEARTBEAT_TAG));
+    assert_eq!(w.stats().sent, 0);
+    w.finish()?;
+    // No heartbeat is sent after the run is ended.
+    let requests = s.requests();
+    let last = |path: &str| requests.iter().rposition(|r| r.path.ends_with(path));
+    assert!(last("/runs/set-tag") < last("/runs/update"));
+    Ok(())
+}
+
+#[test]
+fn find_stale_runs() -> Result<()> {
+    let s = FakeTracking::start();
+    let mlflow = s.mlflow();
+    let stale = start_with(&s, Default::default())?;
+    let alive = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            heartbeat_interval: Some(Duration::from_millis(20)),
+            ..Default::default()
+        },
+    )?;
+    let mut logging = start_with(&s, Default::default())?;
+    logging.log_metric("loss", 1.0, None)?;
+    logging.flush()?;
+    let finished = start_with(&s, Default::default())?;
+    let finished_id = finished.run().id().to_string();
+    finished.finish()?;
+    wait_until(|| {
+        s.state().runs[alive.run().id()]
+            .tags
+            .contains_key(HEARTBEAT_TAG)
+    });
+    for run in s.state().runs.values_mut() {
+        run.info["start_time"] = 0.into();
+    }
+
+    let max_age = Duration::from_secs(60);
+    let ids = |runs: Vec<mlflow_client::MlflowRun>| -> Vec<String> {
+        runs.iter().map(|r| r.id().to_string()).collect()
+    };
+    assert_eq!(
+        ids(mlflow.find_stale_runs("0", max_age)?),
+        [stale.run().id()]
+    );
+    assert_eq!(
+        ids(mlflow.fail_stale_runs("0", max_age)?),
+        [stale.run().id()]
+    );
+    let state = s.state();
+    assert_eq!(state.runs[stale.run().id()].info["status"], "FAILED");
+    assert_eq!(state.runs[alive.run().id()].info["status"], "RUNNING");
+    assert_eq!(state.runs[logging.run().id()].info["status"], "RUNNING");
+    assert_eq!(state.runs[&finished_id].info["status"], "FINISHED");
+    drop(state);
+    assert!(mlflow.find_stale_runs("0", max_age)?.is_empty());
+    alive.finish()?;
+    logging.finish()?;
+    Ok(())
+}
+
+#[test]
+fn progress() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            progress_interval: Duration::ZERO,
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    let mut progress = w.progress(4);
+    for _ in 0..4 {
+        progress.inc(1)?;
+    }
+    assert_eq!(progress.completed(), 4);
+    progress.finish()?;
+    w.finish()?;
+    assert_eq!(
+        history(&s, run.id(), Some("progress")),
+        [
+            (Some(1), 0.25),
+            (Some(2), 0.5),
+            (Some(3), 0.75),
+            (Some(4), 1.0)
+        ]
+    );
+    let eta = history(&s, run.id(), Some("eta_seconds"));
+    assert_eq!(eta.len(), 4);
+    assert_eq!(eta[3], (Some(4), 0.0));
+    let status = &s.state().runs[run.id()].tags[PROGRESS_TAG];
+    assert!(status.starts_with("4/4 (100.0%), done in "), "{status}");
+    Ok(())
+}
+
+#[test]
+fn progress_interval() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            progress_interval: Duration::from_secs(60),
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    let mut progress = w.progress(10);
+    for _ in 0..9 {
+        progress.inc(1)?;
+    }
+    drop(progress);
+    w.finish()?;
+    assert_eq!(
+        history(&s, run.id(), Some("progress")),
+        [(Some(1), 0.1), (Some(9), 0.9)]
+    );
+    let status = &s.state().runs[run.id()].tags[PROGRESS_TAG];
+    assert!(status.starts_with("9/10 (90.0%), ETA "), "{status}");
+    Ok(())
+}
+
+#[test]
+fn track_iterator() -> Result<()> {
+    let s = FakeTracking::start();
+    let w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            progress_interval: Duration::ZERO,
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    let mut items = Vec::new();
+    for i in (0..3).track(&w, 3) {
+        items.push(i);
+    }
+    w.finish()?;
+    assert_eq!(items, [0, 1, 2]);
+    let steps = history(&s, run.id(), Some("progress"))
+        .into_iter()
+        .map(|(step, _)| step)
+        .collect::<Vec<_>>();
+    assert_eq!(steps, [Some(1), Some(2), Some(3)]);
+    Ok(())
+}
+
+/// Environment variable with the directory of the captured output, set when [`output_capture_child`] runs.
+#[cfg(unix)]
+const OUTPUT_DIR: &str = "MLFLOW_CLIENT_TEST_OUTPUT_DIR";
+
+/// Captures the output of the process to a run. Does nothing unless run by [`output_capture`].
+#[cfg(unix)]
+#[test]
+fn output_capture_child() -> Result<()> {
+    use std::{io::Write, path::PathBuf};
+
+    let (Ok(uri), Some(dir)) = (std::env::var(CHILD_URI), std::env::var_os(OUTPUT_DIR)) else {
+        return Ok(());
+    };
+    let dir = PathBuf::from(dir);
+    let e = mlflow_client::Mlflow::new(&uri)?.experiment("0")?.unwrap();
+    let options = MlflowRunWriterOptions {
+        output_capture: Some(OutputCapture {
+            dir: Some(dir.clone()),
+            max_file_size: 100,
+            upload_interval: Duration::from_millis(20),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let w = e.start_run_with_writer_options("run", Default::default(), options.clone())?;
+    let run = w.run().clone();
+    std::io::stdout().write_all(b"hello stdout\n")?;
+    std::io::stderr().write_all(b"hello stderr\n")?;
+    #[cfg(feature = "log")]
+    log::info!("hello log");
+    let expected = [
+        "hello stdout\n",
+        "hello stderr\n",
+        #[cfg(feature = "log")]
+        "hello log\n",
+    ];
+    // Waits for the periodic upload to include everything written so far.
+    wait_until(|| {
+        run.download_artifact(OUTPUT_ARTIFACT)
+            .ok()
+            .and_then(|output| String::from_utf8(output).ok())
+            .is_some_and(|output| expected.iter().all(|l| output.contains(l)))
+    });
+    // Another run cannot capture the output, and does not truncate the file of this run.
+    assert!(e
+        .start_run_with_writer_options("other", Default::default(), options.clone())
+        .is_err());
+    let local = fs::read_to_string(dir.join("output.log"))?;
+    assert!(local.contains("hello stdout\n"), "{local}");
+
+    std::io::stdout().write_all(&[b'x'; 100])?;
+    std::io::stdout().flush()?;
+    wait_until(|| {
+        fs::read_to_string(dir.join("output.log")).is_ok_and(|l| !l.contains("hello stdout"))
+    });
+    std::io::stdout().write_all(b"\nafter rotation\n")?;
+    w.finish()?;
+    std::io::stdout().write_all(b"after finish\n")?;
+
+    let rotated = String::from_utf8(run.download_artifact("logs/output.1.log")?)?;
+    assert!(rotated.contains("hello stdout\n"), "{rotated}");
+    let output = String::from_utf8(run.download_artifact(OUTPUT_ARTIFACT)?)?;
+    assert!(output.contains("after rotation\n"), "{output}");
+    assert!(!output.contains("hello stdout"), "{output}");
+    assert!(!dir.join("output.1.log").exists());
+    let local = fs::read_to_string(dir.join("output.log"))?;
+    assert!(!local.contains("after finish"), "{local}");
+    Ok(())
+}
+
+// Run in a subprocess because the capture redirects the standard output and error of the whole process.
+#[cfg(unix)]
+#[test]
+fn output_capture() -> Result<()> {
+    let s = FakeTracking::start();
+    let dir = TempDir::new("output_capture")?;
+    let status = child_test("output_capture_child", &s)?
+        .env(OUTPUT_DIR, dir.path())
+        .status()?;
+    // Failed assertions of the child are written to its captured output.
+    let output = fs::read_to_string(dir.path().join("output.log")).unwrap_or_default();
+    assert!(status.success(), "{output}");
+    assert_eq!(s.state().runs.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn checkpoints() -> Result<()> {
+    let s = FakeTracking::start();
+    let mut w = start_with(
+        &s,
+        MlflowRunWriterOptions {
+            checkpoint_policy: CheckpointPolicy {
+                keep_last: Some(1),
+                keep_best: Some(KeepBest {
+                    metric: "val_loss".to_string(),
+                    goal: MetricGoal::Minimize,
+                    count: 1,
+                }),
+            },
+            ..Default::default()
+        },
+    )?;
+    let run = w.run().clone();
+    assert!(run
+        .latest_checkpoint(TempDir::new("latest")?.path())?
+        .is_none());
+    let dir = TempDir::new("checkpoint")?;
+    fs::create_dir(dir.path().join("optimizer"))?;
+    for (step, val_loss) in [0.5, 0.2, 0.4, 0.3].into_iter().enumerate() {
+        let step = step as i64;
+        fs::write(dir.path().join("model.bin"), format!("model {step}"))?;
+        fs::write(
+            dir.path().join("optimizer/state.bin"),
+            format!("state {step}"),
+        )?;
+        w.log_metric("val_loss", val_loss, Some(step))?;
+        let c = w.save_checkpoint(step, dir.path())?;
+        assert_eq!(c.files, ["model.bin", "optimizer/state.bin"]);
+        assert_eq!(c.metrics["val_loss"], val_loss);
+    }
+    w.finish()?;
+
+    let steps = run
+        .checkpoints()?
+        .iter()
+        .map(|c| c.step)
+        .collect::<Vec<_>>();
+    assert_eq!(steps, [1, 3]);
+    let artifacts = s.state().artifacts.keys().cloned().collect::<Vec<_>>();
+    assert!(artifacts
+        .iter()
+        .any(|a| a.ends_with("checkpoints/step-1/model.bin")));
+    assert!(!artifacts.iter().any(|a| a.contains("checkpoints/step-0/")));
+    assert!(!artifacts.iter().any(|a| a.contains("checkpoints/step-2/")));
+
+    let latest = TempDir::new("latest")?;
+    let c = run.latest_checkpoint(latest.path())?.unwrap();
+    assert_eq!(c.step, 3);
+    assert_eq!(
+        fs::read_to_string(latest.path().join("model.bin"))?,
+        "model 3"
+    );
+    assert_eq!(
+        fs::read_to_string(latest.path().join("optimizer/state.bin"))?,
+        "state 3"
+    );
+    let best = TempDir::new("best")?;
+    let c = run
+        .best_checkpoint("val_loss", MetricGoal::Minimize, best.path())?
+        .unwrap();
+    assert_eq!(c.step, 1);
+    assert_eq!(
+        fs::read_to_string(best.path().join("model.bin"))?,
+        "model 1"
+    );
+    assert!(run
+        .best_checkpoint("acc", MetricGoal::Maximize, best.path())?
+        .is_none());
+
+    // File names that would be written outside the directory are rejected.
+    let outside = TempDir::new("outside")?;
+    let dir = outside.path().join("dir");
+    for name in ["../model.bin", "/model.bin", "a/../../model.bin", ""] {
+        let mut invalid = c.clone();
+        invalid.files.push(name.to_string());
+        assert!(run.download_checkpoint(&invalid, &dir).is_err(), "{name}");
+    }
+    assert!(!dir.exists());
+    assert!(!outside.path().join("model.bin").exists());
+    Ok(())
+}
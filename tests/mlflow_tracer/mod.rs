@@ -0,0 +1,126 @@
+use std::thread::spawn;
+
+use anyhow::Result;
+use mlflow_client::data::{DeleteTracesOptions, SpanStatusCode, SpanType, TraceStatus, TraceTag};
+use serde_json::json;
+
+use crate::stand_in::tracking::FakeTracking;
+
+#[test]
+fn span_tree() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let tracer = e.tracer();
+
+    let root = tracer.span_with_type("answer", SpanType::Chain);
+    root.set_inputs(json!({ "question": "q" }))?;
+    {
+        let retrieve = tracer.span_with_type("retrieve", SpanType::Retriever);
+        retrieve.add_event("cache_miss", json!({ "key": "q" }))?;
+        let child = retrieve.child("rerank");
+        spawn(move || child.end()).join().unwrap()?;
+    }
+    let llm = tracer.span_with_type("llm", SpanType::Llm);
+    llm.set_outputs("a")?;
+    drop(llm);
+    root.set_outputs("a")?;
+    let request_id = root.request_id().unwrap();
+    root.end()?;
+
+    let c = s.mlflow_client();
+    let info = c.get_trace_info(&request_id)?.trace_info;
+    assert_eq!(info.status, TraceStatus::Ok);
+    assert!(info
+        .request_metadata
+        .iter()
+        .any(|m| m.key == "mlflow.traceInputs" && m.value == r#"{"question":"q"}"#));
+
+    let data = c.get_trace_data(&info)?;
+    let names = data
+        .spans
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["answer", "retrieve", "rerank", "llm"]);
+    let span = |name: &str| data.spans.iter().find(|s| s.name == name).unwrap();
+    let root_id = Some(span("answer").context.span_id.clone());
+    assert_eq!(span("answer").parent_id, None);
+    assert_eq!(span("retrieve").parent_id, root_id);
+    assert_eq!(
+        span("rerank").parent_id,
+        Some(span("retrieve").context.span_id.clone())
+    );
+    assert_eq!(span("llm").parent_id, root_id);
+    assert!(data
+        .spans
+        .iter()
+        .all(|s| s.context.trace_id == span("answer").context.trace_id));
+    assert!(data.spans.iter().all(|s| s.end_time.is_some()));
+    assert_eq!(span("llm").attribute("mlflow.spanType"), Some(json!("LLM")));
+    assert_eq!(
+        span("llm").attribute("mlflow.spanOutputs"),
+        Some(json!("a"))
+    );
+    assert_eq!(
+        span("llm").attribute("mlflow.traceRequestId"),
+        Some(json!(request_id))
+    );
+    assert_eq!(span("retrieve").events[0].name, "cache_miss");
+    Ok(())
+}
+
+#[test]
+fn error_status() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let root = e.tracer().span("root");
+    root.set_status(SpanStatusCode::Error, "failed");
+    let request_id = root.request_id().unwrap();
+    root.end()?;
+
+    let info = s.mlflow_client().get_trace_info(&request_id)?.trace_info;
+    assert_eq!(info.status, TraceStatus::Error);
+    Ok(())
+}
+
+#[test]
+fn sequential_traces() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let tracer = e.tracer();
+    tracer.span("a").end()?;
+    tracer.span("b").end()?;
+    assert_eq!(e.traces()?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn trace_tags_and_delete() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let root = e.tracer().span("root");
+    let request_id = root.request_id().unwrap();
+    root.end()?;
+
+    let c = s.mlflow_client();
+    c.set_trace_tag(&request_id, "t1", "v1")?;
+    let info = c.get_trace_info(&request_id)?.trace_info;
+    assert!(info.tags.contains(&TraceTag {
+        key: "t1".to_string(),
+        value: "v1".to_string()
+    }));
+    c.delete_trace_tag(&request_id, "t1")?;
+    let info = c.get_trace_info(&request_id)?.trace_info;
+    assert_eq!(info.tag("t1"), None);
+
+    let r = c.delete_traces(
+        "0",
+        DeleteTracesOptions {
+            request_ids: &[&request_id],
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(r.traces_deleted, 1);
+    assert!(e.traces()?.is_empty());
+    Ok(())
+}
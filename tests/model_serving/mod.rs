@@ -0,0 +1,114 @@
+use anyhow::Result;
+use mlflow_client::client::{
+    serving::{DataframeSplit, ModelServingClient, ServingInput},
+    MlflowClient,
+};
+use serde_json::{json, Map, Value};
+
+use crate::stand_in::{Request, Response, StandInServer};
+
+fn scoring_server() -> StandInServer {
+    StandInServer::start(|r: &Request| match (r.method.as_str(), r.path.as_str()) {
+        ("GET", "/ping") | ("GET", "/health") => Response::text(200, "\n"),
+        ("GET", "/version") => Response::text(200, "2.17.2"),
+        ("POST", "/invocations") => {
+            let body = r.json();
+            if body.get("inputs") == Some(&json!("bad")) {
+                return Response::json_with_status(
+                    400,
+                    json!({ "error_code": "BAD_REQUEST", "message": "invalid input" }),
+                );
+            }
+            Response::json(json!({ "predictions": body }))
+        }
+        _ => Response::text(404, "not found"),
+    })
+}
+
+#[test]
+fn ping_health_version() -> Result<()> {
+    let s = scoring_server();
+    let c = ModelServingClient::new(&s.uri())?;
+    c.ping()?;
+    c.health()?;
+    assert_eq!(c.version()?, "2.17.2");
+    let paths = s.requests().into_iter().map(|r| r.path).collect::<Vec<_>>();
+    assert_eq!(paths, ["/ping", "/health", "/version"]);
+    Ok(())
+}
+
+#[test]
+fn from_mlflow_client() -> Result<()> {
+    let s = scoring_server();
+    let c = MlflowClient::new("http://localhost:5000")?.model_serving_client(&s.uri())?;
+    c.ping()?;
+    assert_eq!(c.version()?, "2.17.2");
+    Ok(())
+}
+
+#[test]
+fn invocations_formats() -> Result<()> {
+    let s = scoring_server();
+    let c = ModelServingClient::new(&s.uri())?;
+
+    let input = ServingInput::DataframeSplit(DataframeSplit {
+        columns: vec!["a".to_string(), "b".to_string()],
+        data: vec![vec![json!(1), json!(2)]],
+        index: None,
+    });
+    let r = c.invocations(&input, None)?;
+    assert_eq!(
+        r.predictions,
+        json!({ "dataframe_split": { "columns": ["a", "b"], "data": [[1, 2]] } })
+    );
+
+    let mut record = Map::new();
+    record.insert("a".to_string(), json!(1));
+    let r = c.invocations(&ServingInput::DataframeRecords(vec![record]), None)?;
+    assert_eq!(r.predictions, json!({ "dataframe_records": [{ "a": 1 }] }));
+
+    let r = c.invocations(&ServingInput::Instances(json!([1, 2])), None)?;
+    assert_eq!(r.predictions, json!({ "instances": [1, 2] }));
+
+    let mut params = Map::new();
+    params.insert("temperature".to_string(), json!(0.5));
+    let r = c.invocations(&ServingInput::Inputs(json!([[1.0]])), Some(&params))?;
+    assert_eq!(
+        r.predictions,
+        json!({ "inputs": [[1.0]], "params": { "temperature": 0.5 } })
+    );
+    Ok(())
+}
+
+#[test]
+fn predict() -> Result<()> {
+    let s =
+        StandInServer::start(|_: &Request| Response::json(json!({ "predictions": [0.5, 1.5] })));
+    let c = ModelServingClient::new(&s.uri())?;
+    let p: Vec<f64> = c.predict(&ServingInput::Inputs(json!([[1.0], [2.0]])), None)?;
+    assert_eq!(p, vec![0.5, 1.5]);
+    Ok(())
+}
+
+#[test]
+fn predict_legacy_response() -> Result<()> {
+    let s = StandInServer::start(|_: &Request| Response::json(json!([1, 2])));
+    let c = ModelServingClient::new(&s.uri())?;
+    let p: Value = c.predict(&ServingInput::Inputs(json!([[1.0], [2.0]])), None)?;
+    assert_eq!(p, json!([1, 2]));
+    Ok(())
+}
+
+#[test]
+fn invocations_error() -> Result<()> {
+    let s = scoring_server();
+    let c = ModelServingClient::new(&s.uri())?;
+    let e = c
+        .invocations(&ServingInput::Inputs(json!("bad")), None)
+        .unwrap_err();
+    assert!(
+        matches!(&e, mlflow_client::Error::ApiError { error_code, .. } if error_code == "BAD_REQUEST"),
+        "{e}"
+    );
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use mlflow_client::{
+    data::TraceStatus,
+    opentelemetry::{MlflowSpanExporter, MlflowSpanExporterOptions},
+};
+use opentelemetry::{
+    trace::{Span, Status, TraceContextExt, Tracer, TracerProvider},
+    Context, KeyValue,
+};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use serde_json::json;
+
+use crate::stand_in::tracking::FakeTracking;
+
+#[test]
+fn export_spans() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(e.span_exporter())
+        .build();
+    let tracer = provider.tracer("test");
+
+    let root = tracer.start("agent");
+    let cx = Context::current_with_span(root);
+    {
+        let mut chat = tracer.start_with_context("chat", &cx);
+        chat.set_attribute(KeyValue::new("gen_ai.operation.name", "chat"));
+        chat.set_attribute(KeyValue::new(
+            "gen_ai.input.messages",
+            r#"[{"role":"user"}]"#,
+        ));
+        chat.add_event("retry", vec![KeyValue::new("attempt", 1)]);
+        chat.end();
+    }
+    let root = cx.span();
+    root.set_attribute(KeyValue::new("input.value", "hello"));
+    root.set_status(Status::error("failed"));
+    root.end();
+    provider.shutdown()?;
+
+    let traces = e.traces()?;
+    assert_eq!(traces.len(), 1);
+    let info = &traces[0];
+    assert_eq!(info.status, TraceStatus::Error);
+    assert_eq!(info.tag("mlflow.traceName"), Some("agent"));
+    assert!(info
+        .request_metadata
+        .iter()
+        .any(|m| m.key == "mlflow.traceInputs" && m.value == r#""hello""#));
+
+    let c = s.mlflow_client();
+    let info = c.get_trace_info(&info.request_id)?.trace_info;
+    let data = c.get_trace_data(&info)?;
+    let span = |name: &str| data.spans.iter().find(|s| s.name == name).unwrap();
+    assert_eq!(span("agent").parent_id, None);
+    assert_eq!(
+        span("chat").parent_id,
+        Some(span("agent").context.span_id.clone())
+    );
+    assert_eq!(
+        span("chat").attribute("mlflow.spanType"),
+        Some(json!("CHAT_MODEL"))
+    );
+    assert_eq!(
+        span("chat").attribute("mlflow.spanInputs"),
+        Some(json!([{ "role": "user" }]))
+    );
+    assert_eq!(
+        span("chat").attribute("mlflow.traceRequestId"),
+        Some(json!(info.request_id))
+    );
+    assert_eq!(span("chat").events[0].name, "retry");
+    Ok(())
+}
+
+fn exporter(s: &FakeTracking, max_retries: u32) -> MlflowSpanExporter {
+    let options = MlflowSpanExporterOptions {
+        max_retries,
+        retry_interval: Duration::from_millis(10),
+        ..Default::default()
+    };
+    MlflowSpanExporter::with_options(s.mlflow_client(), "0", options)
+}
+
+#[test]
+fn retry_failed_export() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    s.state().start_trace_errors = vec!["TEMPORARILY_UNAVAILABLE"; 2];
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter(&s, 2))
+        .build();
+    provider.tracer("test").start("agent").end();
+    provider.shutdown()?;
+
+    let starts = s
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "POST" && r.path == "/api/2.0/mlflow/traces")
+        .count();
+    assert_eq!(starts, 3);
+    assert_eq!(e.traces()?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn do_not_retry_non_transient_error() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    s.state().start_trace_errors = vec!["INVALID_PARAMETER_VALUE"];
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter(&s, 2))
+        .build();
+    provider.tracer("test").start("agent").end();
+    assert!(provider.shutdown().is_err());
+
+    let starts = s
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "POST" && r.path == "/api/2.0/mlflow/traces")
+        .count();
+    assert_eq!(starts, 1);
+    assert!(e.traces()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn report_export_error() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    s.state().start_trace_errors = vec!["TEMPORARILY_UNAVAILABLE"; 2];
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter(&s, 1))
+        .build();
+    let tracer = provider.tracer("test");
+    tracer.start("lost").end();
+    tracer.start("agent").end();
+    assert!(provider.shutdown().is_err());
+
+    // Traces after the failed one are still exported.
+    let traces = e.traces()?;
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].tag("mlflow.traceName"), Some("agent"));
+    Ok(())
+}
+
+#[test]
+fn report_spans_after_root() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+    let options = MlflowSpanExporterOptions {
+        pending_trace_timeout: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let exporter = MlflowSpanExporter::with_options(s.mlflow_client(), "0", options);
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .build();
+    let tracer = provider.tracer("test");
+    let cx = Context::current_with_span(tracer.start("agent"));
+    let mut chat = tracer.start_with_context("chat", &cx);
+    cx.span().end();
+    chat.end();
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(provider.shutdown().is_err());
+
+    let traces = e.traces()?;
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].tag("mlflow.traceName"), Some("agent"));
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::{fs, path::Path, process::Command};
+
+use anyhow::Result;
+use mlflow_client::{
+    data::{CreateRunOptions, RunTag},
+    run_context::{
+        self, GitRunContext, RunContextProvider, GIT_BRANCH_TAG, GIT_COMMIT_TAG, GIT_REPO_URL_TAG,
+        SOURCE_TYPE_TAG,
+    },
+};
+use tempdir::TempDir;
+
+use crate::stand_in::tracking::FakeTracking;
+
+struct TeamContext;
+impl RunContextProvider for TeamContext {
+    fn tags(&self) -> Vec<RunTag> {
+        vec![RunTag {
+            key: "team".to_string(),
+            value: "vision".to_string(),
+        }]
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    anyhow::ensure!(output.status.success(), "git {args:?} failed");
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+#[test]
+fn git_run_context_tags() -> Result<()> {
+    let dir = TempDir::new("run_context")?;
+    let repo = dir.path();
+    git(repo, &["init", "-q"])?;
+    git(repo, &["checkout", "-q", "-b", "work"])?;
+    git(
+        repo,
+        &["remote", "add", "origin", "https://example.com/repo.git"],
+    )?;
+    fs::write(repo.join("a.txt"), "a")?;
+    git(repo, &["add", "."])?;
+    git(
+        repo,
+        &[
+            "-c",
+            "user.name=a",
+            "-c",
+            "user.email=a@example.com",
+            "commit",
+            "-qm",
+            "init",
+        ],
+    )?;
+    let commit = git(repo, &["rev-parse", "HEAD"])?;
+    fs::create_dir(repo.join("sub"))?;
+
+    let tags = GitRunContext.tags_in(&repo.join("sub"));
+    let value = |key: &str| tags.iter().find(|t| t.key == key).map(|t| t.value.as_str());
+    assert_eq!(value(GIT_COMMIT_TAG), Some(commit.as_str()));
+    assert_eq!(value(GIT_BRANCH_TAG), Some("work"));
+    assert_eq!(
+        value(GIT_REPO_URL_TAG),
+        Some("https://example.com/repo.git")
+    );
+
+    git(repo, &["checkout", "-q", "--detach"])?;
+    let tags = GitRunContext.tags_in(repo);
+    assert!(tags.iter().all(|t| t.key != GIT_BRANCH_TAG));
+    assert!(tags.iter().any(|t| t.key == GIT_COMMIT_TAG));
+
+    let empty = TempDir::new("run_context")?;
+    assert!(GitRunContext.tags_in(empty.path()).is_empty());
+    Ok(())
+}
+
+// All assertions are in one test because the run context is global.
+#[test]
+fn run_context_tags() -> Result<()> {
+    let s = FakeTracking::start();
+    let e = s.mlflow().experiment("0")?.unwrap();
+
+    let run_id = e.start_run("run")?.run().id().to_string();
+    let tags = s.state().runs[&run_id].tags.clone();
+    assert_eq!(tags[SOURCE_TYPE_TAG], "LOCAL");
+    assert!(!tags.contains_key("team"));
+
+    let id = run_context::register(TeamContext);
+    let explicit = [RunTag {
+        key: SOURCE_TYPE_TAG.to_string(),
+        value: "JOB".to_string(),
+    }];
+    let options = CreateRunOptions {
+        tags: &explicit,
+        ..Default::default()
+    };
+    let w = e.start_run_with("run", options)?;
+    let child = w.start_child_run("child")?;
+    for id in [w.run().id(), child.run().id()] {
+        let tags = s.state().runs[id].tags.clone();
+        assert_eq!(tags["team"], "vision");
+    }
+    assert_eq!(s.state().runs[w.run().id()].tags[SOURCE_TYPE_TAG], "JOB");
+
+    assert!(run_context::unregister(id));
+    assert!(!run_context::unregister(id));
+    let run_id = e.start_run("run")?.run().id().to_string();
+    assert!(!s.state().runs[&run_id].tags.contains_key("team"));
+
+    run_context::set_enabled(false);
+    let run_id = e.start_run("run")?.run().id().to_string();
+    run_context::set_enabled(true);
+    assert!(s.state().runs[&run_id].tags.is_empty());
+    Ok(())
+}
@@ -0,0 +1,469 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use mlflow_client::{client::MlflowClient, Mlflow};
+use serde_json::{json, Value};
+
+use super::{Request, Response, StandInServer};
+
+/// In-memory stand-in for the subset of the MLflow Tracking Server REST API used by the high-level types.
+pub struct FakeTracking {
+    server: StandInServer,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+pub struct State {
+    pub runs: BTreeMap<String, FakeRun>,
+    pub logged_models: BTreeMap<String, Value>,
+    pub traces: BTreeMap<String, Value>,
+    pub artifacts: BTreeMap<String, Vec<u8>>,
+    /// Error codes returned by the next `log-batch` requests, in order.
+    pub log_batch_errors: Vec<&'static str>,
+    /// Number of `log-batch` requests that succeed before `log_batch_errors` are returned.
+    pub log_batch_errors_after: usize,
+    /// Error codes returned by the next requests to start a trace, in order.
+    pub start_trace_errors: Vec<&'static str>,
+    next_id: u64,
+}
+impl State {
+    fn new_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}{:032x}", self.next_id)
+    }
+}
+
+pub struct FakeRun {
+    pub info: Value,
+    pub metrics: Vec<Value>,
+    pub params: Vec<(String, String)>,
+    pub tags: BTreeMap<String, String>,
+}
+impl FakeRun {
+    pub fn to_json(&self) -> Value {
+        let mut latest = BTreeMap::<String, Value>::new();
+        for m in &self.metrics {
+            latest.insert(m["key"].as_str().unwrap().to_string(), m.clone());
+        }
+        json!({
+            "info": self.info,
+            "data": {
+                "metrics": latest.into_values().collect::<Vec<_>>(),
+                "params": self.params.iter().map(|(k, v)| json!({ "key": k, "value": v })).collect::<Vec<_>>(),
+                "tags": self.tags.iter().map(|(k, v)| json!({ "key": k, "value": v })).collect::<Vec<_>>(),
+            },
+            "inputs": { "dataset_inputs": [] },
+        })
+    }
+}
+
+impl FakeTracking {
+    pub fn start() -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+        let server = StandInServer::start({
+            let state = state.clone();
+            move |r: &Request| handle(&mut state.lock().unwrap(), r)
+        });
+        Self { server, state }
+    }
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+    pub fn mlflow(&self) -> Mlflow {
+        Mlflow::new(&self.uri()).unwrap()
+    }
+    pub fn mlflow_client(&self) -> MlflowClient {
+        MlflowClient::new(&self.uri()).unwrap()
+    }
+    pub fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+    pub fn requests(&self) -> Vec<Request> {
+        self.server.requests()
+    }
+}
+
+fn not_found() -> Response {
+    Response::json_with_status(
+        404,
+        json!({ "error_code": "RESOURCE_DOES_NOT_EXIST", "message": "not found" }),
+    )
+}
+
+fn injected_error(error_code: &str) -> Response {
+    let status = if error_code == "TEMPORARILY_UNAVAILABLE" {
+        503
+    } else {
+        400
+    };
+    Response::json_with_status(
+        status,
+        json!({ "error_code": error_code, "message": "injected error" }),
+    )
+}
+
+fn default_experiment() -> Value {
+    json!({
+        "experiment_id": "0",
+        "name": "Default",
+        "artifact_location": "mlflow-artifacts:/0",
+        "lifecycle_stage": "active",
+        "last_update_time": 0,
+        "creation_time": 0,
+    })
+}
+
+fn handle(state: &mut State, r: &Request) -> Response {
+    if let Some(path) = r.path.strip_prefix("/api/2.0/mlflow-artifacts/artifacts/") {
+        return handle_artifacts(state, r, &percent_decode(path));
+    }
+    let Some(path) = r.path.strip_prefix("/api/2.0/mlflow/") else {
+        return not_found();
+    };
+    let segments = path.split('/').collect::<Vec<_>>();
+    match (r.method.as_str(), segments.as_slice()) {
+        ("GET", ["experiments", "get"])
+            if r.query_param("experiment_id").as_deref() == Some("0") =>
+        {
+            Response::json(json!({ "experiment": default_experiment() }))
+        }
+        ("GET", ["experiments", "get-by-name"])
+            if r.query_param("experiment_name").as_deref() == Some("Default") =>
+        {
+            Response::json(json!({ "experiment": default_experiment() }))
+        }
+        ("POST", ["runs", "create"]) => {
+            let body = r.json();
+            let run_id = state.new_id("");
+            let experiment_id = body["experiment_id"].as_str().unwrap().to_string();
+            let info = json!({
+                "run_id": run_id,
+                "run_name": body["run_name"],
+                "experiment_id": experiment_id,
+                "status": "RUNNING",
+                "start_time": body["start_time"].as_i64().unwrap_or(0),
+                "artifact_uri": format!("mlflow-artifacts:/{experiment_id}/{run_id}/artifacts"),
+                "lifecycle_stage": "active",
+            });
+            let mut tags = BTreeMap::new();
+            for tag in body["tags"].as_array().into_iter().flatten() {
+                tags.insert(
+                    tag["key"].as_str().unwrap().to_string(),
+                    tag["value"].as_str().unwrap().to_string(),
+                );
+            }
+            let run = FakeRun {
+                info,
+                metrics: Vec::new(),
+                params: Vec::new(),
+                tags,
+            };
+            let response = json!({ "run": run.to_json() });
+            state.runs.insert(run_id, run);
+            Response::json(response)
+        }
+        ("POST", ["runs", "search"]) => {
+            let body = r.json();
+            let experiment_ids = body["experiment_ids"].as_array().unwrap();
+            // Only `tags.<key> = '<value>'` and `attributes.<key> = '<value>'` filters are supported.
+            let filter = body["filter"].as_str().unwrap_or("");
+            let filter = filter.split_once('.').map(|(kind, f)| {
+                let (key, value) = f.split_once(" = ").unwrap();
+                (kind, key.trim_matches('`'), value.trim_matches('\''))
+            });
+            let runs = state
+                .runs
+                .values()
+                .filter(|run| experiment_ids.contains(&run.info["experiment_id"]))
+                .filter(|run| match filter {
+                    Some(("tags", key, value)) => {
+                        run.tags.get(key).map(|v| v.as_str()) == Some(value)
+                    }
+                    Some(("attributes", key, value)) => run.info[key] == value,
+                    Some((kind, ..)) => panic!("unsupported filter: {kind}"),
+                    None => true,
+                })
+                .map(|run| run.to_json())
+                .collect::<Vec<_>>();
+            Response::json(json!({ "runs": runs }))
+        }
+        ("GET", ["runs", "get"]) => match state.runs.get(&r.query_param("run_id").unwrap()) {
+            Some(run) => Response::json(json!({ "run": run.to_json() })),
+            None => not_found(),
+        },
+        ("GET", ["metrics", "get-history"]) => {
+            let Some(run) = state.runs.get(&r.query_param("run_id").unwrap()) else {
+                return not_found();
+            };
+            let key = r.query_param("metric_key").unwrap();
+            let metrics = run
+                .metrics
+                .iter()
+                .filter(|m| m["key"] == key.as_str())
+                .cloned()
+                .collect::<Vec<_>>();
+            Response::json(json!({ "metrics": metrics }))
+        }
+        ("POST", ["runs", "update"]) => {
+            let body = r.json();
+            let Some(run) = state.runs.get_mut(body["run_id"].as_str().unwrap()) else {
+                return not_found();
+            };
+            for key in ["status", "end_time", "run_name"] {
+                if !body[key].is_null() {
+                    run.info[key] = body[key].clone();
+                }
+            }
+            Response::json(json!({ "run_info": run.info }))
+        }
+        ("POST", ["runs", "log-batch"]) => {
+            if state.log_batch_errors_after > 0 {
+                state.log_batch_errors_after -= 1;
+            } else if !state.log_batch_errors.is_empty() {
+                return injected_error(state.log_batch_errors.remove(0));
+            }
+            let body = r.json();
+            let Some(run) = state.runs.get_mut(body["run_id"].as_str().unwrap()) else {
+                return not_found();
+            };
+            run.metrics
+                .extend(body["metrics"].as_array().unwrap().iter().cloned());
+            for p in body["params"].as_array().unwrap() {
+                run.params.push((
+                    p["key"].as_str().unwrap().to_string(),
+                    p["value"].as_str().unwrap().to_string(),
+                ));
+            }
+            for t in body["tags"].as_array().unwrap() {
+                run.tags.insert(
+                    t["key"].as_str().unwrap().to_string(),
+                    t["value"].as_str().unwrap().to_string(),
+                );
+            }
+            Response::json(json!({}))
+        }
+        ("POST", ["runs", "set-tag"]) => {
+            let body = r.json();
+            let Some(run) = state.runs.get_mut(body["run_id"].as_str().unwrap()) else {
+                return not_found();
+            };
+            run.tags.insert(
+                body["key"].as_str().unwrap().to_string(),
+                body["value"].as_str().unwrap().to_string(),
+            );
+            Response::json(json!({}))
+        }
+        ("POST", ["runs", "log-parameter"]) => {
+            let body = r.json();
+            let Some(run) = state.runs.get_mut(body["run_id"].as_str().unwrap()) else {
+                return not_found();
+            };
+            run.params.push((
+                body["key"].as_str().unwrap().to_string(),
+                body["value"].as_str().unwrap().to_string(),
+            ));
+            Response::json(json!({}))
+        }
+        ("POST", ["logged-models"]) => {
+            let body = r.json();
+            let model_id = state.new_id("m-");
+            let model = json!({
+                "info": {
+                    "model_id": model_id,
+                    "experiment_id": body["experiment_id"],
+                    "name": body["name"],
+                    "creation_timestamp_ms": 0,
+                    "last_updated_timestamp_ms": 0,
+                    "artifact_uri": format!("mlflow-artifacts:/0/models/{model_id}/artifacts"),
+                    "status": "LOGGED_MODEL_PENDING",
+                    "model_type": body["model_type"],
+                    "source_run_id": body["source_run_id"],
+                    "tags": body["tags"],
+                },
+                "data": { "params": body["params"] },
+            });
+            state.logged_models.insert(model_id, model.clone());
+            Response::json(json!({ "model": model }))
+        }
+        ("POST", ["logged-models", "search"]) => Response::json(json!({
+            "models": state.logged_models.values().cloned().collect::<Vec<_>>(),
+        })),
+        ("GET", ["logged-models", model_id]) => match state.logged_models.get(*model_id) {
+            Some(model) => Response::json(json!({ "model": model })),
+            None => not_found(),
+        },
+        ("PATCH", ["logged-models", model_id]) => {
+            let body = r.json();
+            let Some(model) = state.logged_models.get_mut(*model_id) else {
+                return not_found();
+            };
+            model["info"]["status"] = body["status"].clone();
+            Response::json(json!({ "model": model }))
+        }
+        ("DELETE", ["logged-models", model_id]) => match state.logged_models.remove(*model_id) {
+            Some(_) => Response::json(json!({})),
+            None => not_found(),
+        },
+        ("PATCH", ["logged-models", model_id, "tags"]) => {
+            let body = r.json();
+            let Some(model) = state.logged_models.get_mut(*model_id) else {
+                return not_found();
+            };
+            let mut tags = model["info"]["tags"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            for t in body["tags"].as_array().unwrap() {
+                tags.retain(|x| x["key"] != t["key"]);
+                tags.push(t.clone());
+            }
+            model["info"]["tags"] = Value::Array(tags);
+            Response::json(json!({}))
+        }
+        ("DELETE", ["logged-models", model_id, "tags", key]) => {
+            let Some(model) = state.logged_models.get_mut(*model_id) else {
+                return not_found();
+            };
+            if let Some(tags) = model["info"]["tags"].as_array_mut() {
+                tags.retain(|t| t["key"] != *key);
+            }
+            Response::json(json!({}))
+        }
+        ("POST", ["logged-models", model_id, "params"]) => {
+            let body = r.json();
+            let Some(model) = state.logged_models.get_mut(*model_id) else {
+                return not_found();
+            };
+            let mut params = model["data"]["params"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            params.extend(body["params"].as_array().unwrap().iter().cloned());
+            model["data"]["params"] = Value::Array(params);
+            Response::json(json!({}))
+        }
+        ("POST", ["traces"]) => {
+            if !state.start_trace_errors.is_empty() {
+                return injected_error(state.start_trace_errors.remove(0));
+            }
+            let body = r.json();
+            let request_id = state.new_id("tr-");
+            let experiment_id = body["experiment_id"].as_str().unwrap().to_string();
+            let mut tags = body["tags"].as_array().cloned().unwrap_or_default();
+            tags.push(json!({
+                "key": "mlflow.artifactLocation",
+                "value": format!("mlflow-artifacts:/{experiment_id}/traces/{request_id}/artifacts"),
+            }));
+            let info = json!({
+                "request_id": request_id,
+                "experiment_id": experiment_id,
+                "timestamp_ms": body["timestamp_ms"],
+                "status": "IN_PROGRESS",
+                "request_metadata": body["request_metadata"],
+                "tags": tags,
+            });
+            state.traces.insert(request_id, info.clone());
+            Response::json(json!({ "trace_info": info }))
+        }
+        ("GET", ["traces"]) => {
+            let experiment_id = r.query_param("experiment_ids").unwrap();
+            let traces = state
+                .traces
+                .values()
+                .filter(|t| t["experiment_id"] == experiment_id.as_str())
+                .cloned()
+                .collect::<Vec<_>>();
+            Response::json(json!({ "traces": traces }))
+        }
+        ("POST", ["traces", "delete-traces"]) => {
+            let body = r.json();
+            let mut deleted = 0;
+            for id in body["request_ids"].as_array().unwrap() {
+                if state.traces.remove(id.as_str().unwrap()).is_some() {
+                    deleted += 1;
+                }
+            }
+            Response::json(json!({ "traces_deleted": deleted }))
+        }
+        ("PATCH", ["traces", request_id]) => {
+            let body = r.json();
+            let Some(info) = state.traces.get_mut(*request_id) else {
+                return not_found();
+            };
+            let start = info["timestamp_ms"].as_i64().unwrap();
+            info["execution_time_ms"] = json!(body["timestamp_ms"].as_i64().unwrap() - start);
+            info["status"] = body["status"].clone();
+            info["request_metadata"] = body["request_metadata"].clone();
+            Response::json(json!({ "trace_info": info }))
+        }
+        ("GET", ["traces", request_id, "info"]) => match state.traces.get(*request_id) {
+            Some(info) => Response::json(json!({ "trace_info": info })),
+            None => not_found(),
+        },
+        ("PATCH", ["traces", request_id, "tags"]) => {
+            let body = r.json();
+            let Some(info) = state.traces.get_mut(*request_id) else {
+                return not_found();
+            };
+            let tags = info["tags"].as_array_mut().unwrap();
+            tags.retain(|t| t["key"] != body["key"]);
+            tags.push(json!({ "key": body["key"], "value": body["value"] }));
+            Response::json(json!({}))
+        }
+        ("DELETE", ["traces", request_id, "tags"]) => {
+            let body = r.json();
+            let Some(info) = state.traces.get_mut(*request_id) else {
+                return not_found();
+            };
+            info["tags"]
+                .as_array_mut()
+                .unwrap()
+                .retain(|t| t["key"] != body["key"]);
+            Response::json(json!({}))
+        }
+        _ => not_found(),
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let hex = tail.get(..2).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(decoded) if b == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).unwrap()
+}
+
+fn handle_artifacts(state: &mut State, r: &Request, path: &str) -> Response {
+    match r.method.as_str() {
+        "PUT" => {
+            state.artifacts.insert(path.to_string(), r.body.clone());
+            Response::json(json!({}))
+        }
+        "GET" => match state.artifacts.get(path) {
+            Some(data) => Response {
+                status: 200,
+                content_type: "application/octet-stream",
+                body: data.clone(),
+            },
+            None => not_found(),
+        },
+        "DELETE" => match state.artifacts.remove(path) {
+            Some(_) => Response::json(json!({})),
+            None => not_found(),
+        },
+        _ => not_found(),
+    }
+}
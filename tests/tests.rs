@@ -9,8 +9,18 @@ use ::mlflow_client::{client::MlflowClient, Mlflow};
 use fs2::FileExt;
 use tempdir::TempDir;
 
+mod build_info;
 mod mlflow;
 mod mlflow_client;
+mod mlflow_logged_model;
+mod mlflow_run;
+mod mlflow_run_writer;
+mod mlflow_tracer;
+mod model_serving;
+#[cfg(feature = "opentelemetry")]
+mod opentelemetry;
+mod run_context;
+mod stand_in;
 
 mod data;
 